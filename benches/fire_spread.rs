@@ -0,0 +1,56 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use project_forest_fire::clusters::Clusters;
+use project_forest_fire::fire_spread::{FireSpreadStrategy, MooreNeighborhood, VonNeumannNeighborhood};
+use project_forest_fire::forest::Forest;
+use project_forest_fire::simulation::rng_from_seed;
+
+const SIZES: [usize; 2] = [256, 1280];
+
+/// One full fire from a seeded lightning strike until the front goes out.
+fn burn(size: usize, density: f64, strategy: &dyn FireSpreadStrategy) -> usize {
+    let mut rng = rng_from_seed(42);
+    let mut forest = Forest::new(size, size, density, &mut rng);
+    let strike = forest.pick_random_tree(&mut rng);
+    forest.ignite(strike, &mut rng);
+    while !forest.fire_spread(strategy, &mut rng) {}
+    forest.burned_count
+}
+
+fn bench_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("forest_new");
+    for size in SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter(|| Forest::new(size, size, 0.6, &mut rng_from_seed(42)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_burn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("full_burn");
+    group.sample_size(10);
+    for size in SIZES {
+        group.bench_with_input(BenchmarkId::new("moore", size), &size, |b, &size| {
+            b.iter(|| burn(size, 0.6, &MooreNeighborhood))
+        });
+        group.bench_with_input(BenchmarkId::new("vonneumann", size), &size, |b, &size| {
+            b.iter(|| burn(size, 0.7, &VonNeumannNeighborhood))
+        });
+    }
+    group.finish();
+}
+
+fn bench_clusters(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clusters");
+    group.sample_size(10);
+    for size in SIZES {
+        let forest = Forest::new(size, size, 0.6, &mut rng_from_seed(42));
+        group.bench_with_input(BenchmarkId::new("moore", size), &forest, |b, forest| {
+            b.iter(|| Clusters::find(forest, &MooreNeighborhood).count())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_generation, bench_burn, bench_clusters);
+criterion_main!(benches);
@@ -0,0 +1,235 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use crate::forest::Forest;
+
+/// Color of the cells the fire never reached in an arrival image.
+const UNBURNED_RGB: [u8; 3] = [200, 200, 200];
+
+/// Colormap from the first ignition (dark) to the last (bright), interpolated linearly.
+const COLORMAP: [[u8; 3]; 5] = [
+    [20, 11, 52],
+    [120, 28, 109],
+    [207, 68, 70],
+    [251, 155, 6],
+    [252, 255, 164],
+];
+
+/// File formats of an arrival map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrivalFormat {
+    Csv, // one row of comma-separated steps per grid row, empty where nothing burned
+    Png, // one colormapped pixel per cell
+}
+
+impl ArrivalFormat {
+    /// The format of a file by its extension: `.csv` or `.png`.
+    pub fn of(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+            Some("csv") => Some(ArrivalFormat::Csv),
+            Some("png") => Some(ArrivalFormat::Png),
+            _ => None,
+        }
+    }
+}
+
+/// File name of the arrival map of a run, `{run}` replaced by its index.
+pub fn path_for(path: &str, run: usize) -> PathBuf {
+    PathBuf::from(path.replace("{run}", &run.to_string()))
+}
+
+/// Timestep at which every cell of a forest caught fire.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrivalMap {
+    pub width: usize,
+    pub height: usize,
+    times: Vec<Option<u32>>, // row-major, None where the fire never arrived
+}
+
+impl ArrivalMap {
+    pub fn from_forest(forest: &Forest) -> Self {
+        ArrivalMap { width: forest.width, height: forest.height, times: forest.arrival_times().collect() }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<u32> {
+        self.times[y * self.width + x]
+    }
+
+    /// First and last arrival step, None when nothing burned.
+    pub fn span(&self) -> Option<(u32, u32)> {
+        let mut burned = self.times.iter().flatten();
+        let first = *burned.next()?;
+        Some(burned.fold((first, first), |(lo, hi), &t| (lo.min(t), hi.max(t))))
+    }
+
+    /// Rate of spread in cells per step: the least-squares slope of the distance from
+    /// the ignition (the centroid of the first cells lit) to the farthest cell lit at
+    /// each step. Only steps up to the first one reaching the grid edge are fitted, as
+    /// the edge cuts the front short. None with fewer than two steps to fit.
+    pub fn rate_of_spread(&self) -> Option<f64> {
+        fit_rate(self.width, self.height, self.times.iter().copied())
+    }
+
+    /// Writes the map to a file in the format of its extension.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let format = ArrivalFormat::of(path)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "save arrival times to a .csv or .png file"))?;
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write(format, &mut out)?;
+        out.flush()
+    }
+
+    pub fn write(&self, format: ArrivalFormat, out: &mut dyn Write) -> io::Result<()> {
+        match format {
+            ArrivalFormat::Csv => {
+                for row in self.times.chunks_exact(self.width) {
+                    let fields: Vec<String> = row.iter().map(|t| t.map_or_else(String::new, |t| t.to_string())).collect();
+                    writeln!(out, "{}", fields.join(","))?;
+                }
+                Ok(())
+            }
+            ArrivalFormat::Png => {
+                let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+                encoder.set_color(png::ColorType::Rgb);
+                let mut writer = encoder.write_header()?;
+                writer.write_image_data(&self.rgb_pixels())?;
+                Ok(writer.finish()?)
+            }
+        }
+    }
+
+    /// RGB bytes of the colormapped arrival times, one pixel per cell.
+    fn rgb_pixels(&self) -> Vec<u8> {
+        let (first, last) = self.span().unwrap_or((0, 0));
+        let range = (last - first).max(1) as f64;
+        self.times
+            .iter()
+            .flat_map(|t| match t {
+                Some(t) => colormap((t - first) as f64 / range),
+                None => UNBURNED_RGB,
+            })
+            .collect()
+    }
+}
+
+/// Rate of spread of the fire in `forest`, as [`ArrivalMap::rate_of_spread`], read
+/// straight from its arrival times without building a map.
+pub fn rate_of_spread(forest: &Forest) -> Option<f64> {
+    fit_rate(forest.width, forest.height, forest.arrival_times())
+}
+
+/// Rate of spread from the row-major arrival times of a `width` × `height` grid.
+fn fit_rate(width: usize, height: usize, times: impl Iterator<Item = Option<u32>> + Clone) -> Option<f64> {
+    let mut burned = times.clone().flatten();
+    let first = burned.next()?;
+    let (first, last) = burned.fold((first, first), |(lo, hi), t| (lo.min(t), hi.max(t)));
+    let cells = || times.clone().enumerate().filter_map(|(index, t)| t.map(|t| (index % width, index / width, t)));
+
+    let origins: Vec<(usize, usize)> = cells().filter(|&(_, _, t)| t == first).map(|(x, y, _)| (x, y)).collect();
+    let cx = origins.iter().map(|&(x, _)| x as f64).sum::<f64>() / origins.len() as f64;
+    let cy = origins.iter().map(|&(_, y)| y as f64).sum::<f64>() / origins.len() as f64;
+
+    let on_edge = |x: usize, y: usize| x == 0 || y == 0 || x + 1 == width || y + 1 == height;
+    let end = cells().filter(|&(x, y, _)| on_edge(x, y)).map(|(_, _, t)| t).min().unwrap_or(last);
+
+    let mut farthest = vec![None; (end - first) as usize + 1];
+    for (x, y, t) in cells().filter(|&(_, _, t)| t <= end) {
+        let distance = (x as f64 - cx).hypot(y as f64 - cy);
+        let slot = &mut farthest[(t - first) as usize];
+        *slot = Some(slot.map_or(distance, |d: f64| d.max(distance)));
+    }
+
+    let points: Vec<(f64, f64)> = farthest
+        .iter()
+        .enumerate()
+        .filter_map(|(step, d)| d.map(|d| (step as f64, d)))
+        .collect();
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_t = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_d = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|(t, d)| (t - mean_t) * (d - mean_d)).sum();
+    let variance: f64 = points.iter().map(|(t, _)| (t - mean_t).powi(2)).sum();
+    Some(covariance / variance)
+}
+
+/// Color of `value` from 0.0 to 1.0 in the colormap.
+fn colormap(value: f64) -> [u8; 3] {
+    let position = value.clamp(0.0, 1.0) * (COLORMAP.len() - 1) as f64;
+    let lower = (position.floor() as usize).min(COLORMAP.len() - 2);
+    let fraction = position - lower as f64;
+    let (a, b) = (COLORMAP[lower], COLORMAP[lower + 1]);
+    [0, 1, 2].map(|c| (a[c] as f64 + (b[c] as f64 - a[c] as f64) * fraction).round() as u8)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fire_spread::{FireSpreadStrategy, MooreNeighborhood, VonNeumannNeighborhood};
+    use crate::simulation::rng_from_seed;
+
+    /// Arrival map of a fire lit in the middle of a full forest.
+    fn burn_full_forest(strategy: &dyn FireSpreadStrategy) -> ArrivalMap {
+        let mut rng = rng_from_seed(1);
+        let mut forest = Forest::new(41, 41, 1.0, &mut rng);
+        forest.ignite(Some((20, 20)), &mut rng);
+        while !forest.fire_spread(strategy, &mut rng) {}
+        let map = ArrivalMap::from_forest(&forest);
+        // the forest measures the same rate without the map
+        assert_eq!(rate_of_spread(&forest), map.rate_of_spread());
+        map
+    }
+
+    #[test]
+    fn test_front_speeds_of_the_neighborhoods() {
+        // the Moore front is a square reaching √2 cells a step along the diagonals,
+        // the von Neumann front a diamond reaching one cell a step along the axes
+        let moore = burn_full_forest(&MooreNeighborhood);
+        assert_eq!(moore.get(20, 20), Some(0));
+        assert_eq!(moore.get(25, 12), Some(8));
+        assert_eq!(moore.span(), Some((0, 20)));
+        assert!((moore.rate_of_spread().unwrap() - 2f64.sqrt()).abs() < 1e-9);
+
+        let von_neumann = burn_full_forest(&VonNeumannNeighborhood);
+        assert_eq!(von_neumann.get(25, 12), Some(13));
+        assert_eq!(von_neumann.span(), Some((0, 40)));
+        assert!((von_neumann.rate_of_spread().unwrap() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unburned_forest_has_no_rate() {
+        let forest = Forest::new(5, 5, 0.5, &mut rng_from_seed(3));
+        let map = ArrivalMap::from_forest(&forest);
+        assert_eq!(map.span(), None);
+        assert_eq!(map.rate_of_spread(), None);
+    }
+
+    #[test]
+    fn test_csv_and_png() {
+        let mut rng = rng_from_seed(4);
+        let mut forest = Forest::new(3, 2, 1.0, &mut rng);
+        forest.set(1, 1, crate::forest::CellState::Empty);
+        forest.ignite(Some((0, 0)), &mut rng);
+        while !forest.fire_spread(&VonNeumannNeighborhood, &mut rng) {}
+        let map = ArrivalMap::from_forest(&forest);
+
+        let mut csv = Vec::new();
+        map.write(ArrivalFormat::Csv, &mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "0,1,2\n1,,3\n");
+
+        let pixels = map.rgb_pixels();
+        assert_eq!(pixels[..3], COLORMAP[0]);
+        assert_eq!(pixels[15..], COLORMAP[4]);
+        assert_eq!(pixels[12..15], UNBURNED_RGB);
+
+        let path = std::env::temp_dir().join(format!("forest_arrival_{}.png", std::process::id()));
+        map.save(&path).unwrap();
+        let reader = png::Decoder::new(File::open(&path).unwrap()).read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (3, 2));
+        std::fs::remove_file(path).unwrap();
+        assert!(map.save(Path::new("arrival.txt")).is_err());
+    }
+}
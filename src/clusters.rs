@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use crate::fire_spread::{Boundary, FireSpreadStrategy};
+use crate::forest::{CellState, Forest};
+use crate::spanning::wrapping;
+
+/// Connected clusters of the trees standing in a forest, two trees joined when one
+/// is a neighbor of the other under the spread strategy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clusters {
+    pub trees: usize,
+    sizes: Vec<usize>, // trees of every cluster, largest first
+    spanning: bool,    // some cluster touches two opposite edges, or wraps around a torus
+}
+
+impl Clusters {
+    /// Labels the clusters with a union-find over the cells (Hoshen–Kopelman without
+    /// the relabeling pass); burning trees of a map count as trees.
+    pub fn find(forest: &Forest, strategy: &dyn FireSpreadStrategy) -> Self {
+        let cells = forest.cells();
+        let is_tree = |index: usize| matches!(cells[index], CellState::Tree | CellState::Burning);
+        let bounds = forest.bounds();
+
+        let mut sets = DisjointSets::new(cells.len());
+        for index in (0..cells.len()).filter(|&index| is_tree(index)) {
+            let (x, y) = forest.coords(index);
+            strategy.for_each_neighbor(x, y, bounds, &mut |nx, ny| {
+                let neighbor = forest.index(nx, ny);
+                if is_tree(neighbor) {
+                    sets.union(index, neighbor);
+                }
+            });
+        }
+
+        // a torus has no edges to join, a cluster percolates by closing on itself around it
+        let spanning = if forest.boundary == Boundary::Periodic {
+            let (wraps_x, wraps_y) = wrapping(forest, strategy, |x, y| is_tree(forest.index(x, y)));
+            wraps_x || wraps_y
+        } else {
+            // edges every cluster touches: left, right, top and bottom bits of its root
+            let mut edges = vec![0u8; cells.len()];
+            for index in (0..cells.len()).filter(|&index| is_tree(index)) {
+                let (x, y) = forest.coords(index);
+                let root = sets.find(index);
+                edges[root] |= (x == 0) as u8
+                    | ((x + 1 == forest.width) as u8) << 1
+                    | ((y == 0) as u8) << 2
+                    | ((y + 1 == forest.height) as u8) << 3;
+            }
+            edges.iter().any(|&e| e & 0b0011 == 0b0011 || e & 0b1100 == 0b1100)
+        };
+
+        let mut sizes: Vec<usize> = (0..cells.len())
+            .filter(|&index| is_tree(index) && sets.parent[index] == index)
+            .map(|root| sets.size[root])
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        Clusters { trees: sizes.iter().sum(), sizes, spanning }
+    }
+
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Trees of every cluster, largest first.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    pub fn largest(&self) -> usize {
+        self.sizes.first().copied().unwrap_or(0)
+    }
+
+    /// Whether a cluster joins the left and right or the top and bottom edge, or under
+    /// periodic edges wraps around the torus: the forest percolates.
+    pub fn spans(&self) -> bool {
+        self.spanning
+    }
+
+    /// Trees of the largest cluster over all trees, in percent; NaN without trees.
+    pub fn largest_percent(&self) -> f64 {
+        self.largest() as f64 / self.trees as f64 * 100.0
+    }
+
+    /// Mean size of the cluster a tree outside the largest one belongs to, Σs²/Σs over
+    /// the other clusters: the susceptibility, which peaks at the percolation threshold.
+    /// 0.0 when the largest cluster is the only one.
+    pub fn mean_size(&self) -> f64 {
+        let rest = self.sizes.iter().skip(1);
+        let trees: usize = rest.clone().sum();
+        if trees == 0 {
+            return 0.0;
+        }
+        rest.map(|&s| (s * s) as f64).sum::<f64>() / trees as f64
+    }
+
+    /// Number of clusters of every size, by increasing size.
+    pub fn distribution(&self) -> Vec<(usize, usize)> {
+        let mut counts = BTreeMap::new();
+        for &size in &self.sizes {
+            *counts.entry(size).or_insert(0) += 1;
+        }
+        counts.into_iter().collect()
+    }
+}
+
+/// Union-find with path halving and union by size.
+struct DisjointSets {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        DisjointSets { parent: (0..n).collect(), size: vec![1; n] }
+    }
+
+    fn find(&mut self, mut index: usize) -> usize {
+        while self.parent[index] != index {
+            self.parent[index] = self.parent[self.parent[index]];
+            index = self.parent[index];
+        }
+        index
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fire_spread::{Boundary, MooreNeighborhood, VonNeumannNeighborhood};
+    use crate::map::ForestMap;
+
+    fn forest(rows: &[&str]) -> Forest {
+        Forest::from_map(&ForestMap::parse_ascii(&rows.join("\n")).unwrap())
+    }
+
+    #[test]
+    fn test_neighborhoods_join_diagonals_or_not() {
+        let forest = forest(&[
+            "TT..T",
+            "..T.T",
+            "T..T.",
+        ]);
+        let von_neumann = Clusters::find(&forest, &VonNeumannNeighborhood);
+        assert_eq!(von_neumann.sizes(), &[2, 2, 1, 1, 1]);
+        assert_eq!(von_neumann.distribution(), vec![(1, 3), (2, 2)]);
+        assert_eq!(von_neumann.trees, 7);
+
+        // (1,0)-(2,1)-(3,2)-(4,1) join diagonally under Moore
+        let moore = Clusters::find(&forest, &MooreNeighborhood);
+        assert_eq!(moore.sizes(), &[6, 1]);
+        assert!((moore.largest_percent() - 600.0 / 7.0).abs() < 1e-9);
+        assert_eq!(moore.mean_size(), 1.0);
+    }
+
+    #[test]
+    fn test_susceptibility_skips_the_largest() {
+        let forest = forest(&[
+            "TTT.T",
+            "....T",
+            "TT...",
+        ]);
+        let clusters = Clusters::find(&forest, &VonNeumannNeighborhood);
+        assert_eq!(clusters.sizes(), &[3, 2, 2]);
+        assert_eq!(clusters.mean_size(), 2.0);
+        assert_eq!(clusters.count(), 3);
+        assert!(!clusters.spans());
+
+        let spanning = self::forest(&[
+            ".T...",
+            ".TT..",
+            "..T..",
+        ]);
+        assert!(Clusters::find(&spanning, &VonNeumannNeighborhood).spans());
+    }
+
+    #[test]
+    fn test_periodic_edges_join_clusters() {
+        let forest = forest(&["T.T"]);
+        assert_eq!(Clusters::find(&forest, &VonNeumannNeighborhood).count(), 2);
+        let wrapped = forest.with_boundary(Boundary::Periodic);
+        let clusters = Clusters::find(&wrapped, &VonNeumannNeighborhood);
+        assert_eq!(clusters.sizes(), &[2]);
+        assert_eq!(clusters.mean_size(), 0.0);
+        // the two trees meet across the seam without going around the grid
+        assert!(!clusters.spans());
+
+        let band = self::forest(&[
+            "....",
+            "TTTT",
+            "....",
+        ]);
+        assert!(Clusters::find(&band.with_boundary(Boundary::Periodic), &VonNeumannNeighborhood).spans());
+    }
+}
@@ -1,72 +1,458 @@
 // use std::env;
-use crate::fire_spread::{MooreNeighborhood, VonNeumannNeighborhood};
+use std::path::Path;
+use std::sync::Arc;
+use crate::arrival::ArrivalFormat;
+use crate::fire_spread::{Boundary, FireSpreadStrategy, MooreNeighborhood, VonNeumannNeighborhood, WindStrategy};
+use crate::drossel_schwabl::DrosselSchwabl;
+use crate::map::ForestMap;
+use crate::placement::Placement;
+use crate::recording::{RecordFormat, Recording};
+use crate::scaling::Scaling;
+use crate::seasons::{Recovery, Seasons};
+use crate::snapshot::{SnapshotStep, Snapshots};
+use crate::sweep::{density_steps, parse_densities, Axis, Sweep, DEFAULT_SWEEP_STEP};
+use crate::terrain::{Terrain, TerrainSource};
+use crate::threshold::{Criterion, Threshold};
+use crate::vegetation::{Vegetation, VegetationMix, VegetationTable};
 
 // Burn pattern options
 #[derive(Debug, Clone, Copy)]
 pub enum BurnPattern {
     Moore(MooreNeighborhood),      // 8-directional
     VonNeumann(VonNeumannNeighborhood), // 4-directional
+    Wind(WindStrategy),                 // 8-directional, skewed downwind
+}
+
+impl BurnPattern {
+    pub fn strategy(&self) -> &dyn FireSpreadStrategy {
+        match self {
+            BurnPattern::Moore(s) => s,
+            BurnPattern::VonNeumann(s) => s,
+            BurnPattern::Wind(s) => s,
+        }
+    }
+
+    /// The pattern of a `--burn-pattern` name; `wind` blows for the wind pattern.
+    pub fn parse(name: &str, wind: WindStrategy) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "moore" => Some(BurnPattern::Moore(MooreNeighborhood)),
+            "vonneumann" => Some(BurnPattern::VonNeumann(VonNeumannNeighborhood)),
+            "wind" => Some(BurnPattern::Wind(wind)),
+            _ => None,
+        }
+    }
+
+    /// Name as accepted by `--burn-pattern`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BurnPattern::Moore(_) => "moore",
+            BurnPattern::VonNeumann(_) => "vonneumann",
+            BurnPattern::Wind(_) => "wind",
+        }
+    }
+}
+
+// Simulation model options
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Model {
+    #[default]
+    SingleFire,                     // one lightning strike on a static forest
+    DrosselSchwabl(DrosselSchwabl), // regrowth and lightning on an ever-evolving forest
+    Seasons(Seasons),               // one fire a season, then years of recovery
+    Threshold(Threshold),           // bisection for the critical density
+}
+
+// Result output options
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
 }
 
 
 // Configuration structure
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Config {
-    pub size: usize,
+    pub width: usize,
+    pub height: usize,
     pub density: f64,
     pub simulations: usize,
     pub burn_pattern: BurnPattern,
+    pub model: Model,
+    pub boundary: Boundary,
+    pub spread_prob: f64,
+    pub vegetation: VegetationMix, // which classes grow, `density` is their total
+    pub placement: Placement,      // how the trees are laid out
+    pub map: Option<Arc<ForestMap>>, // fixed layout replacing the generated forest
+    pub classes: VegetationTable,  // ignition and fuel of every class
+    pub terrain: TerrainSource,
+    pub relief: f64,           // terrain height range, in cell widths
+    pub slope_factor: f64,     // spread weight exponent per radian of slope
+    pub shade_elevation: bool, // tint the display by terrain height
     pub graphics: bool,
     pub frame_delay_ms: u64,
     pub quiet: bool,
-    pub auto_sweep: bool,
-    pub sweep_step: Option<f64>,
+    pub sweep: Option<Sweep>,  // parameters to vary, one batch per combination
+    pub seed: Option<u64>,     // master seed, random when not given
+    pub replay: Option<usize>, // run only this simulation index of the batch
+    pub threads: usize,        // worker threads for headless runs
+    pub output_format: OutputFormat,
+    pub record_runs: bool,     // keep a record of every run in the results
+    pub clusters: bool,        // label the tree clusters of every forest before its fire
+    pub snapshots: Option<Snapshots>, // forests to save during every fire
+    pub recording: Option<Recording>, // animated image of every fire
+    pub arrival: Option<String>,      // arrival-time map of every fire, with an optional `{run}`
+    pub scaling: Option<Scaling>,     // finite-size scaling study over several grid sizes
 }
 
-impl Config {
-    pub fn new(args: &[String]) -> Result<Self, String> {
-        let mut config = Config {
-            size: 20,
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            width: 20,
+            height: 20,
             density: 0.6,
             simulations: 1,
             burn_pattern: BurnPattern::Moore(MooreNeighborhood),
+            model: Model::SingleFire,
+            boundary: Boundary::Open,
+            spread_prob: 1.0,
+            vegetation: VegetationMix::default(),
+            placement: Placement::Uniform,
+            map: None,
+            classes: VegetationTable::default(),
+            terrain: TerrainSource::Flat,
+            relief: 10.0,
+            slope_factor: 4.5,
+            shade_elevation: false,
             graphics: true,
             frame_delay_ms: 50,
             quiet: false,
-            auto_sweep: false,
-            sweep_step: None,
-        };
+            sweep: None,
+            seed: None,
+            replay: None,
+            threads: 1,
+            output_format: OutputFormat::Text,
+            record_runs: false,
+            clusters: false,
+            snapshots: None,
+            recording: None,
+            arrival: None,
+            scaling: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn new(args: &[String]) -> Result<Self, String> {
+        let mut config = Config::default();
 
         // .skip(1) to ignore the program name
         let mut args_iter = args.iter().skip(1);
+        // the pattern named by -b and the first wind option, which must agree
+        let mut named_pattern: Option<BurnPattern> = None;
+        let mut wind_option: Option<&str> = None;
+        // grid size and density options, which a scaling study sets itself
+        let mut grid_option: Option<&str> = None;
+        // density, vegetation and placement options, which an input map sets itself
+        let mut stand_option: Option<&str> = None;
 
         while let Some(arg) = args_iter.next() {
             match arg.as_str() {
                 "-s" | "--size" => {
+                    // either a square side "400" or "<width>x<height>" like "400x50"
+                    let size = parse_arg::<String>(&mut args_iter, "size")?;
+                    (config.width, config.height) = parse_size(&size)?;
+                    grid_option.get_or_insert(arg);
+                }
+                "-W" | "--width" => {
                     // parse_arg will take <T> based on the type the variable is
                     // so instead of doing:
-                    // config.size = parse_arg::<usize>(&mut args_iter, "size")?;
+                    // config.width = parse_arg::<usize>(&mut args_iter, "width")?;
                     // we can just do:
-                    config.size = parse_arg(&mut args_iter, "size")?;
+                    config.width = parse_arg(&mut args_iter, "width")?;
                     // and the compiler will tell the parse_arg that it should take <usize>
+                    grid_option.get_or_insert(arg);
+                }
+                "-H" | "--height" => {
+                    config.height = parse_arg(&mut args_iter, "height")?;
+                    grid_option.get_or_insert(arg);
                 }
                 "-d" | "--density" => {
                     config.density = parse_arg(&mut args_iter, "density")?;
                     if !(0.0..=1.0).contains(&config.density) {
                         return Err("Density must be between 0.0 and 1.0".into());
                     }
+                    grid_option.get_or_insert(arg);
+                    stand_option.get_or_insert(arg);
                 }
                 "-c" | "--simulations" => {
                     config.simulations = parse_arg(&mut args_iter, "simulations")?;
                 }
                 "-b" | "--burn-pattern" => {
                     let pattern = parse_arg::<String>(&mut args_iter, "burn-pattern")?;
-                    config.burn_pattern = match pattern.to_lowercase().as_str() {
-                        "moore" => BurnPattern::Moore(MooreNeighborhood),
-                        "vonneumann" => BurnPattern::VonNeumann(VonNeumannNeighborhood),
-                        _ => return Err("Invalid burn pattern. Use 'moore' or 'vonneumann'".into()),
+                    config.burn_pattern = BurnPattern::parse(&pattern, config.wind())
+                        .ok_or("Invalid burn pattern. Use 'moore', 'vonneumann' or 'wind'")?;
+                    named_pattern = Some(config.burn_pattern);
+                }
+                // the wind options select the wind pattern themselves
+                "--wind-dir" => {
+                    let value = parse_arg::<String>(&mut args_iter, "wind-dir")?;
+                    let direction = WindStrategy::parse_direction(&value)
+                        .ok_or("Invalid wind direction. Use N, NE, E, SE, S, SW, W, NW or degrees")?;
+                    config.burn_pattern = BurnPattern::Wind(WindStrategy { direction, ..config.wind() });
+                    wind_option.get_or_insert(arg);
+                }
+                "--wind-strength" => {
+                    let strength: f64 = parse_arg(&mut args_iter, "wind-strength")?;
+                    if !(0.0..=10.0).contains(&strength) {
+                        return Err("Wind strength must be between 0.0 and 10.0".into());
+                    }
+                    config.burn_pattern = BurnPattern::Wind(WindStrategy { strength, ..config.wind() });
+                    wind_option.get_or_insert(arg);
+                }
+                "--wind-reach" => {
+                    let reach: usize = parse_arg(&mut args_iter, "wind-reach")?;
+                    if !(1..=2).contains(&reach) {
+                        return Err("Wind reach must be 1 or 2".into());
+                    }
+                    config.burn_pattern = BurnPattern::Wind(WindStrategy { reach, ..config.wind() });
+                    wind_option.get_or_insert(arg);
+                }
+                "--model" => {
+                    let model = parse_arg::<String>(&mut args_iter, "model")?;
+                    config.model = match model.to_lowercase().as_str() {
+                        "single" => Model::SingleFire,
+                        "drossel-schwabl" | "ds" => Model::DrosselSchwabl(config.drossel_schwabl()),
+                        "seasons" => Model::Seasons(config.seasons()),
+                        "threshold" => Model::Threshold(config.threshold()),
+                        _ => return Err("Invalid model. Use 'single', 'drossel-schwabl', 'seasons' or 'threshold'".into()),
+                    };
+                }
+                // the model options select the Drossel–Schwabl model themselves
+                "--growth" => {
+                    let growth: f64 = parse_arg(&mut args_iter, "growth")?;
+                    if !(0.0..=1.0).contains(&growth) {
+                        return Err("Growth probability must be between 0.0 and 1.0".into());
+                    }
+                    config.model = Model::DrosselSchwabl(DrosselSchwabl { growth, ..config.drossel_schwabl() });
+                }
+                "--lightning" => {
+                    let lightning: f64 = parse_arg(&mut args_iter, "lightning")?;
+                    if !(0.0..=1.0).contains(&lightning) {
+                        return Err("Lightning probability must be between 0.0 and 1.0".into());
+                    }
+                    config.model = Model::DrosselSchwabl(DrosselSchwabl { lightning, ..config.drossel_schwabl() });
+                }
+                "--ds-steps" => {
+                    let steps = parse_arg(&mut args_iter, "ds-steps")?;
+                    config.model = Model::DrosselSchwabl(DrosselSchwabl { steps, ..config.drossel_schwabl() });
+                }
+                "--warmup" => {
+                    let warmup = parse_arg(&mut args_iter, "warmup")?;
+                    config.model = Model::DrosselSchwabl(DrosselSchwabl { warmup, ..config.drossel_schwabl() });
+                }
+                // the season options select the multi-season model themselves
+                "--seasons" => {
+                    let seasons = parse_arg(&mut args_iter, "seasons")?;
+                    config.model = Model::Seasons(Seasons { seasons, ..config.seasons() });
+                }
+                "--recovery-years" => {
+                    let years = parse_arg(&mut args_iter, "recovery-years")?;
+                    config.model = Model::Seasons(Seasons { years, ..config.seasons() });
+                }
+                "--recovery" => {
+                    let recovery = parse_arg::<String>(&mut args_iter, "recovery")?;
+                    let recovery = match (recovery.to_lowercase().as_str(), config.seasons().recovery) {
+                        ("logistic", current @ Recovery::Logistic { .. }) | ("age", current @ Recovery::Age { .. }) => current,
+                        ("logistic", _) => Recovery::Logistic { rate: 0.5 },
+                        ("age", _) => Recovery::Age { maturity: 20 },
+                        _ => return Err("Invalid recovery. Use 'logistic' or 'age'".into()),
+                    };
+                    config.model = Model::Seasons(Seasons { recovery, ..config.seasons() });
+                }
+                "--regrowth-rate" => {
+                    let rate: f64 = parse_arg(&mut args_iter, "regrowth-rate")?;
+                    if !(rate > 0.0 && rate.is_finite()) {
+                        return Err("Regrowth rate must be a positive number".into());
+                    }
+                    config.model = Model::Seasons(Seasons { recovery: Recovery::Logistic { rate }, ..config.seasons() });
+                }
+                "--maturity" => {
+                    let maturity: u16 = parse_arg(&mut args_iter, "maturity")?;
+                    if maturity == 0 {
+                        return Err("Maturity must be at least 1 year".into());
+                    }
+                    config.model = Model::Seasons(Seasons { recovery: Recovery::Age { maturity }, ..config.seasons() });
+                }
+                // the search options select the threshold search themselves
+                "--criterion" => {
+                    let criterion = parse_arg::<String>(&mut args_iter, "criterion")?;
+                    let criterion = match criterion.to_lowercase().as_str() {
+                        "percolation" => Criterion::Percolation,
+                        "spanning" => Criterion::Spanning,
+                        "burned" => Criterion::Burned,
+                        _ => return Err("Invalid criterion. Use 'percolation', 'spanning' or 'burned'".into()),
+                    };
+                    config.model = Model::Threshold(Threshold { criterion, ..config.threshold() });
+                }
+                "--target" => {
+                    let target: f64 = parse_arg(&mut args_iter, "target")?;
+                    if !(target > 0.0 && target < 1.0) {
+                        return Err("Threshold target must be between 0.0 and 1.0 (exclusive)".into());
+                    }
+                    config.model = Model::Threshold(Threshold { target, ..config.threshold() });
+                }
+                "--tolerance" => {
+                    let tolerance: f64 = parse_arg(&mut args_iter, "tolerance")?;
+                    if !(tolerance > 0.0 && tolerance < 1.0) {
+                        return Err("Threshold tolerance must be between 0.0 and 1.0 (exclusive)".into());
+                    }
+                    config.model = Model::Threshold(Threshold { tolerance, ..config.threshold() });
+                }
+                "--boundary" => {
+                    let boundary = parse_arg::<String>(&mut args_iter, "boundary")?;
+                    config.boundary = match boundary.to_lowercase().as_str() {
+                        "open" => Boundary::Open,
+                        "periodic" => Boundary::Periodic,
+                        _ => return Err("Invalid boundary. Use 'open' or 'periodic'".into()),
+                    };
+                }
+                "-p" | "--spread-prob" => {
+                    config.spread_prob = parse_arg(&mut args_iter, "spread-prob")?;
+                    if !(0.0..=1.0).contains(&config.spread_prob) {
+                        return Err("Spread probability must be between 0.0 and 1.0".into());
+                    }
+                }
+                "--placement" => {
+                    let placement = parse_arg::<String>(&mut args_iter, "placement")?;
+                    config.placement = match placement.to_lowercase().as_str() {
+                        "uniform" => Placement::Uniform,
+                        "noise" => config.noise(),
+                        "poisson" => config.poisson(),
+                        "hurst" => Placement::Hurst { hurst: config.hurst() },
+                        _ => return Err("Invalid placement. Use 'uniform', 'noise', 'poisson' or 'hurst'".into()),
+                    };
+                    stand_option.get_or_insert(arg);
+                }
+                // the clustering parameters select their placement themselves
+                "--noise-scale" => {
+                    let scale: f64 = parse_arg(&mut args_iter, "noise-scale")?;
+                    if !(scale >= 1.0 && scale.is_finite()) {
+                        return Err("Noise scale must be at least 1 cell".into());
+                    }
+                    let Placement::Noise { octaves, .. } = config.noise() else { unreachable!() };
+                    config.placement = Placement::Noise { scale, octaves };
+                    stand_option.get_or_insert(arg);
+                }
+                "--octaves" => {
+                    let octaves: u32 = parse_arg(&mut args_iter, "octaves")?;
+                    if !(1..=16).contains(&octaves) {
+                        return Err("Octaves must be between 1 and 16".into());
+                    }
+                    let Placement::Noise { scale, .. } = config.noise() else { unreachable!() };
+                    config.placement = Placement::Noise { scale, octaves };
+                    stand_option.get_or_insert(arg);
+                }
+                "--parents" => {
+                    let parents: f64 = parse_arg(&mut args_iter, "parents")?;
+                    if !(parents > 0.0 && parents <= 1.0) {
+                        return Err("Parent intensity must be above 0.0 and at most 1.0".into());
+                    }
+                    let Placement::Poisson { radius, .. } = config.poisson() else { unreachable!() };
+                    config.placement = Placement::Poisson { parents, radius };
+                    stand_option.get_or_insert(arg);
+                }
+                "--cluster-radius" => {
+                    let radius: f64 = parse_arg(&mut args_iter, "cluster-radius")?;
+                    if !(radius > 0.0 && radius.is_finite()) {
+                        return Err("Cluster radius must be a positive number".into());
+                    }
+                    let Placement::Poisson { parents, .. } = config.poisson() else { unreachable!() };
+                    config.placement = Placement::Poisson { parents, radius };
+                    stand_option.get_or_insert(arg);
+                }
+                "--hurst" => {
+                    let hurst: f64 = parse_arg(&mut args_iter, "hurst")?;
+                    if !(0.0..=1.0).contains(&hurst) {
+                        return Err("Hurst exponent must be between 0.0 and 1.0".into());
+                    }
+                    config.placement = Placement::Hurst { hurst };
+                    stand_option.get_or_insert(arg);
+                }
+                // the grid takes the size of the map
+                "--input-map" => {
+                    let path = parse_arg::<String>(&mut args_iter, "input-map")?;
+                    let map = ForestMap::load(Path::new(&path))?;
+                    (config.width, config.height) = (map.width, map.height);
+                    config.map = Some(Arc::new(map));
+                }
+                "--vegetation" => {
+                    let spec = parse_arg::<String>(&mut args_iter, "vegetation")?;
+                    (config.vegetation, config.density) = VegetationMix::parse(&spec)?;
+                    stand_option.get_or_insert(arg);
+                }
+                // the class options take "<value>" for every fuel class or "<class>=<value>,..."
+                "--ignition" => {
+                    let spec = parse_arg::<String>(&mut args_iter, "ignition")?;
+                    for (class, ignition) in parse_per_class(&spec, "ignition", |v| {
+                        v.parse::<f64>().ok().filter(|p| (0.0..=1.0).contains(p))
+                    })? {
+                        config.classes[class].ignition = ignition;
+                    }
+                }
+                "--burn-steps" => {
+                    let spec = parse_arg::<String>(&mut args_iter, "burn-steps")?;
+                    for (class, burn_steps) in parse_per_class(&spec, "burn-steps", |v| {
+                        v.parse::<u16>().ok().filter(|&n| n > 0)
+                    })? {
+                        config.classes[class].fuel.burn_steps = burn_steps;
+                    }
+                }
+                "--spread-phase" => {
+                    let spec = parse_arg::<String>(&mut args_iter, "spread-phase")?;
+                    for (class, (from, until)) in parse_per_class(&spec, "spread-phase", parse_phase)? {
+                        (config.classes[class].fuel.spread_from, config.classes[class].fuel.spread_until) = (from, until);
+                    }
+                }
+                "--terrain" => {
+                    let terrain = parse_arg::<String>(&mut args_iter, "terrain")?;
+                    config.terrain = match terrain.to_lowercase().as_str() {
+                        "flat" => TerrainSource::Flat,
+                        "diamond-square" => TerrainSource::DiamondSquare { roughness: config.roughness() },
+                        _ => return Err("Invalid terrain. Use 'flat' or 'diamond-square', or --heightmap <file>".into()),
                     };
                 }
+                // the roughness selects diamond-square terrain itself
+                "--roughness" => {
+                    let roughness: f64 = parse_arg(&mut args_iter, "roughness")?;
+                    if !(0.0..=1.0).contains(&roughness) {
+                        return Err("Roughness must be between 0.0 and 1.0".into());
+                    }
+                    config.terrain = TerrainSource::DiamondSquare { roughness };
+                }
+                // the grid takes the size of the heightmap
+                "--heightmap" => {
+                    let path = parse_arg::<String>(&mut args_iter, "heightmap")?;
+                    let terrain = Terrain::load(Path::new(&path))?;
+                    (config.width, config.height) = (terrain.width, terrain.height);
+                    config.terrain = TerrainSource::Heightmap(Arc::new(terrain));
+                }
+                "--relief" => {
+                    config.relief = parse_arg(&mut args_iter, "relief")?;
+                    if !(config.relief >= 0.0 && config.relief.is_finite()) {
+                        return Err("Relief must be a non-negative number".into());
+                    }
+                }
+                "--slope-factor" => {
+                    config.slope_factor = parse_arg(&mut args_iter, "slope-factor")?;
+                    if !config.slope_factor.is_finite() {
+                        return Err("Invalid value for slope-factor".into());
+                    }
+                }
+                "--shade-elevation" => {
+                    config.shade_elevation = true;
+                }
                 "-g-off" | "--graphics-off" => {
                     config.graphics = false;
                 }
@@ -80,8 +466,13 @@ impl Config {
                     }
                 }
 
+                // the auto sweep is a density sweep from 0 to 1
                 "--auto-sweep" | "-a" => {
-                    config.auto_sweep = true;
+                    let mut sweep = config.sweep.take().unwrap_or_default();
+                    if sweep.axis("density").is_none() {
+                        sweep.push(Axis::Density(density_steps(DEFAULT_SWEEP_STEP)));
+                    }
+                    config.sweep = Some(sweep);
                 }
                 "--sweep-step" | "-ss" => {
                     let step: f64 = parse_arg(&mut args_iter, "sweep-step")?;
@@ -90,21 +481,183 @@ impl Config {
                             "Step between densities in simulation must be between 0.01 and 0.2".into()
                         );
                     }
-                    config.sweep_step = Some(step);
+                    config.sweep.get_or_insert_default().push(Axis::Density(density_steps(step)));
+                }
+                "--sweep" => {
+                    let axis = Axis::parse(&parse_arg::<String>(&mut args_iter, "sweep")?, config.wind())?;
+                    config.sweep.get_or_insert_default().push(axis);
+                }
+                "--seed" => {
+                    config.seed = Some(parse_arg(&mut args_iter, "seed")?);
+                }
+                "--replay" => {
+                    config.replay = Some(parse_arg(&mut args_iter, "replay")?);
+                }
+                "-t" | "--threads" => {
+                    config.threads = parse_arg(&mut args_iter, "threads")?;
+                    if config.threads == 0 {
+                        return Err("Threads must be at least 1".into());
+                    }
+                }
+                "-o" | "--output-format" => {
+                    let format = parse_arg::<String>(&mut args_iter, "output-format")?;
+                    config.output_format = match format.to_lowercase().as_str() {
+                        "text" => OutputFormat::Text,
+                        "json" => OutputFormat::Json,
+                        "csv" => OutputFormat::Csv,
+                        _ => return Err("Invalid output format. Use 'text', 'json' or 'csv'".into()),
+                    };
+                }
+                "--per-run" => {
+                    config.record_runs = true;
+                }
+                "--clusters" => {
+                    config.clusters = true;
+                }
+                "--snapshot" => {
+                    let path = parse_arg(&mut args_iter, "snapshot")?;
+                    let steps = config.snapshots.take().map_or(vec![SnapshotStep::Initial, SnapshotStep::Final], |s| s.steps);
+                    config.snapshots = Some(Snapshots { path, steps });
+                }
+                // the recording settings start a recording themselves, the file still has to be given
+                "--record" => {
+                    let path = parse_arg(&mut args_iter, "record")?;
+                    config.recording = Some(Recording { path, ..config.recording() });
+                }
+                "--record-delay" => {
+                    let delay_ms: u16 = parse_arg(&mut args_iter, "record-delay")?;
+                    if !(10..=10_000).contains(&delay_ms) {
+                        return Err("Record delay must be between 10 and 10 000 ms".into());
+                    }
+                    config.recording = Some(Recording { delay_ms, ..config.recording() });
+                }
+                "--record-scale" => {
+                    let scale: usize = parse_arg(&mut args_iter, "record-scale")?;
+                    if !(1..=64).contains(&scale) {
+                        return Err("Record scale must be between 1 and 64 pixels per cell".into());
+                    }
+                    config.recording = Some(Recording { scale, ..config.recording() });
+                }
+                "--record-frames" => {
+                    let max_frames: usize = parse_arg(&mut args_iter, "record-frames")?;
+                    if max_frames < 2 {
+                        return Err("A recording needs at least 2 frames".into());
+                    }
+                    config.recording = Some(Recording { max_frames, ..config.recording() });
+                }
+                "--arrival" => {
+                    config.arrival = Some(parse_arg(&mut args_iter, "arrival")?);
+                }
+                // the density list alone still needs the sizes of the study
+                "--sizes" => {
+                    let list = parse_arg::<String>(&mut args_iter, "sizes")?;
+                    let mut sizes = list
+                        .split(',')
+                        .map(|size| size.trim().parse().map_err(|_| format!("Invalid size '{}'", size)))
+                        .collect::<Result<Vec<usize>, _>>()?;
+                    sizes.sort_unstable();
+                    sizes.dedup();
+                    if sizes.len() < 2 || sizes[0] < 2 {
+                        return Err("A scaling study needs at least 2 different sizes of 2 cells or more".into());
+                    }
+                    config.scaling = Some(Scaling { sizes, ..config.scaling() });
+                }
+                "--densities" => {
+                    let densities = parse_densities(&parse_arg::<String>(&mut args_iter, "densities")?)?;
+                    config.scaling = Some(Scaling { densities, ..config.scaling() });
+                }
+                "--snapshot-steps" => {
+                    let list = parse_arg::<String>(&mut args_iter, "snapshot-steps")?;
+                    let steps = list
+                        .split(',')
+                        .map(|step| SnapshotStep::parse(step).ok_or_else(|| format!("Invalid snapshot step '{}'", step)))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let path = config.snapshots.take().map_or_else(String::new, |s| s.path);
+                    config.snapshots = Some(Snapshots { path, steps });
                 }
                 "-h" | "--help" => {
                     return Err(
 "Usage: forest_fire_sim [OPTIONS]
 
 Options:
-    -s, --size <size>              Grid size (default: 20)
+    -s, --size <size>              Grid size, square '<n>' or '<width>x<height>' (default: 20)
+    -W, --width <width>            Grid width (default: 20)
+    -H, --height <height>          Grid height (default: 20)
     -d, --density <density>        Tree density between 0.0 and 1.0 (default: 0.6)
     -c, --simulations <count>      Number of simulations to run (default: 1)
-    -b, --burn-pattern <pattern>   Burn pattern: 'moore' or 'vonneumann' (default: moore)
+    -b, --burn-pattern <pattern>   Burn pattern: 'moore', 'vonneumann' or 'wind' (default: moore)
+    --wind-dir <dir>               Direction the wind blows towards: N, NE, ... NW or degrees (default: E)
+    --wind-strength <s>            Wind strength, 0.0 (calm) to 10.0 (default: 1.0)
+    --wind-reach <1|2>             Let the fire jump 2 cells downwind (default: 1)
+                                   (the wind options select '-b wind' and clash with other patterns)
+    --model <model>                'single' fire, 'drossel-schwabl', 'seasons' or a 'threshold' search
+                                   for the critical density (default: single)
+    --growth <p>                   Drossel–Schwabl tree growth probability per empty cell (default: 0.01)
+    --lightning <f>                Drossel–Schwabl lightning probability per tree (default: 0.0001)
+    --ds-steps <n>                 Drossel–Schwabl steps recorded per run; 0 animates forever (default: 1000)
+    --warmup <n>                   Drossel–Schwabl steps before recording (default: 200)
+    --seasons <n>                  Multi-season regime: fires in a row, with recovery between (default: 10)
+    --recovery-years <n>           Years of regrowth after each fire (default: 10)
+    --recovery <model>             Regrowth of burned cells: 'logistic' or 'age' (default: logistic)
+    --regrowth-rate <r>            Logistic yearly growth rate of the forest cover (default: 0.5)
+    --maturity <years>             Age recovery: years until a burned cell surely regrows (default: 20)
+    --criterion <criterion>        Threshold search: 'percolation' or 'spanning' probability or mean
+                                   'burned' fraction (default: percolation)
+    --target <value>               Value of the criterion at the critical density (default: 0.5)
+    --tolerance <d>                Density bracket at which the bisection stops (default: 0.005)
+    --boundary <boundary>          Grid edges: 'open' or 'periodic' (default: open)
+    -p, --spread-prob <p>          Chance a burning cell ignites each tree neighbor (default: 1.0)
+    --vegetation <class>=<d>,...   Density of each class: grass, shrub, conifer, deciduous, water, rock
+                                   (default: conifer at --density)
+    --input-map <file>             Read the forest from an ASCII, CSV, PGM or PNG map; sets the grid size,
+                                   density, vegetation and placement
+    --placement <placement>        Tree layout: 'uniform', 'noise', 'poisson' or 'hurst' (default: uniform)
+    --noise-scale <cells>          Noise placement: size of the tree patches (default: 8)
+    --octaves <n>                  Noise placement: layers of finer detail, 1 to 16 (default: 4)
+    --parents <λ>                  Poisson placement: cluster centres per cell (default: 0.005)
+    --cluster-radius <σ>           Poisson placement: spread of trees around a centre, in cells (default: 3)
+    --hurst <H>                    Hurst placement: 0.0 (fragmented) to 1.0 (smooth) (default: 0.7)
+    --ignition [<class>=]<p>,...   Ignition chance of a class, scaling --spread-prob
+                                   (default: grass 1, shrub 0.8, conifer 1, deciduous 0.5)
+    --burn-steps [<class>=]<n>,... Timesteps a tree keeps burning
+                                   (default: grass 1, shrub 2, conifer 1, deciduous 3)
+    --spread-phase [<class>=]<first>-<last>,...
+                                   Steps of the burn, from 1, that ignite neighbors (default: all)
+    --terrain <terrain>            Elevation: 'flat' or 'diamond-square' (default: flat)
+    --roughness <r>                Diamond-square roughness, 0.0 (smooth) to 1.0 (default: 0.5)
+    --heightmap <file>             Load elevation from a PGM or text heightmap; sets the grid size
+    --relief <h>                   Height of the highest point above the lowest, in cells (default: 10)
+    --slope-factor <a>             Uphill spread boost per radian of slope (default: 4.5)
+    --shade-elevation              Shade the animation by terrain height
     -g-off, --graphics-off         Disable graphical output (default: enabled)
     -fd, --frame-delay <ms>        Frame delay in milliseconds (1 to 10000, default: 50)
-    -a, --auto-sweep               Automatic mode with default step between densities = 0.05
+    -a, --auto-sweep               Automatic mode: densities up to 1 in steps of 0.05
     -ss --sweep-step <step>        Automatic mode with setting the step between the densities (between 0.01 and 0.2)
+    --sweep <param>=<values>       Sweep 'density', 'size', 'burn-pattern' or 'simulations' over a list
+                                   or a start:stop:num range; repeat for the product of several
+    --seed <u64>                   Master seed for reproducible runs (default: random)
+    --replay <index>               Run only simulation <index> of the seeded batch
+    -t, --threads <n>              Worker threads for headless runs (default: 1)
+    -o, --output-format <format>   Result format: 'text', 'json' or 'csv' (default: text)
+    -q, --quiet                    Print only average burned (raw float, text format)
+    --per-run                      Include a record of every run (seed, trees, burned, steps, ignition)
+    --clusters                     Label the tree clusters before every fire: cluster count, largest
+                                   cluster, mean size and percolation (costs about one more burn)
+    --snapshot <file>              Save the forest to a .png, .bin, .csv or ASCII file; '{run}' and '{step}'
+                                   in the name are replaced by the run index and the step
+    --snapshot-steps <list>        Steps to save: 'initial', 'final' and timesteps (default: initial,final)
+    --record <file>                Record every timestep of the fire to a .gif or .png/.apng animation;
+                                   '{run}' in the name is replaced by the run index
+    --record-delay <ms>            Display time of every recorded frame (default: 100)
+    --record-scale <px>            Pixels per cell side in the recording (default: 2)
+    --record-frames <n>            Most frames recorded; longer fires are sampled evenly (default: 500)
+    --arrival <file>               Save the step each cell caught fire to a .csv matrix or a colormapped
+                                   .png; '{run}' in the name is replaced by the run index
+    --sizes <L>,...                Finite-size scaling study: run every density on square grids of these
+                                   sides, then fit the critical density and the exponent ν
+                                   (replaces -s, -W, -H and -d, which are rejected alongside)
+    --densities <list|a:b:n>       Densities of the study, a list or n points from a to b
+                                   (default: 0.0:1.0:41)
     -h, --help                     Print this help message"
                     .into()
                     );
@@ -113,10 +666,291 @@ Options:
             }
         }
 
+        if config.width == 0 || config.height == 0 {
+            return Err("Grid width and height must be at least 1".into());
+        }
+
+        if let (Some(option), Some(pattern)) = (wind_option, named_pattern)
+            && !matches!(pattern, BurnPattern::Wind(_))
+        {
+            return Err(format!("{} needs the wind burn pattern, not '-b {}'", option, pattern.name()));
+        }
+
+        if let Model::DrosselSchwabl(model) = config.model
+            && model.steps == 0
+            && !config.graphics
+        {
+            return Err("--ds-steps 0 runs forever and needs the animation".into());
+        }
+        if config.model != Model::SingleFire && config.sweep.is_some() {
+            return Err("Sweeps are only available with the single-fire model".into());
+        }
+        // a swept wind pattern blows as configured, whichever option came first
+        let wind = config.wind();
+        if let Some(sweep) = &mut config.sweep {
+            for axis in &mut sweep.axes {
+                if let Axis::BurnPattern(patterns) = axis {
+                    patterns.iter_mut().filter(|p| matches!(p, BurnPattern::Wind(_))).for_each(|p| *p = BurnPattern::Wind(wind));
+                }
+            }
+        }
+
+        if let Some(map) = &config.map {
+            if (map.width, map.height) != (config.width, config.height) {
+                return Err(format!(
+                    "Grid size {}x{} does not match the {}x{} map",
+                    config.width, config.height, map.width, map.height
+                ));
+            }
+            if config.sweep.as_ref().is_some_and(|s| s.axis("density").is_some() || s.axis("size").is_some()) {
+                return Err("A sweep cannot change the density or size of a map".into());
+            }
+            if matches!(config.model, Model::Threshold(_)) {
+                return Err("The threshold search cannot change the density of a map".into());
+            }
+            if let Some(option) = stand_option {
+                return Err(format!("{} cannot be combined with --input-map, the map sets the stand", option));
+            }
+            // the regrowth models strike their own fires
+            if map.is_burning() && matches!(config.model, Model::DrosselSchwabl(_) | Model::Seasons(_)) {
+                return Err(format!("The fire front of {} only spreads under the single-fire model", map.source));
+            }
+            // the map decides what grows where
+            (config.vegetation, config.density) = VegetationMix::from_counts(&map.class_counts(), map.width * map.height);
+        }
+
+        let densest = match config.sweep.as_ref().and_then(|s| s.axis("density")) {
+            Some(Axis::Density(densities)) => densities.iter().copied().fold(config.density, f64::max),
+            _ => config.density,
+        };
+        if densest + config.vegetation.barren() > 1.0 + 1e-9 {
+            return Err("Tree density and water/rock cover add up to more than 1.0".into());
+        }
+
+        for class in Vegetation::ALL.into_iter().filter(|v| v.is_fuel()) {
+            let fuel = config.classes[class].fuel;
+            if fuel.spread_from >= fuel.burn_steps {
+                return Err(format!(
+                    "Spread phase of {} starts after its {} burn steps, the fire could never spread",
+                    class.name(),
+                    fuel.burn_steps
+                ));
+            }
+        }
+
+        if let TerrainSource::Heightmap(terrain) = &config.terrain
+            && (terrain.width, terrain.height) != (config.width, config.height)
+        {
+            return Err(format!(
+                "Grid size {}x{} does not match the {}x{} heightmap",
+                config.width, config.height, terrain.width, terrain.height
+            ));
+        }
+        if matches!(config.terrain, TerrainSource::Heightmap(_)) && config.sweep.as_ref().is_some_and(|s| s.axis("size").is_some()) {
+            return Err("A sweep cannot change the size of a heightmap".into());
+        }
+
+        if let Some(snapshots) = &config.snapshots {
+            if snapshots.path.is_empty() {
+                return Err("--snapshot-steps needs a --snapshot file".into());
+            }
+            if config.model != Model::SingleFire || config.sweep.is_some() {
+                return Err("Snapshots are only taken of single-fire runs".into());
+            }
+            if snapshots.steps.len() > 1 && !snapshots.path.contains("{step}") {
+                return Err("Saving several steps needs '{step}' in the snapshot file name".into());
+            }
+            if config.simulations > 1 && config.replay.is_none() && !snapshots.path.contains("{run}") {
+                return Err("Saving several runs needs '{run}' in the snapshot file name".into());
+            }
+        }
+
+        if let Some(recording) = &config.recording {
+            if recording.path.is_empty() {
+                return Err("The record options need a --record file".into());
+            }
+            if RecordFormat::of(Path::new(&recording.path)).is_none() {
+                return Err("Record to a .gif, .png or .apng file".into());
+            }
+            if config.model != Model::SingleFire || config.sweep.is_some() {
+                return Err("Only single-fire runs can be recorded".into());
+            }
+            if config.simulations > 1 && config.replay.is_none() && !recording.path.contains("{run}") {
+                return Err("Recording several runs needs '{run}' in the file name".into());
+            }
+        }
+
+        if let Some(path) = &config.arrival {
+            if ArrivalFormat::of(Path::new(path)).is_none() {
+                return Err("Save arrival times to a .csv or .png file".into());
+            }
+            if config.model != Model::SingleFire || config.sweep.is_some() {
+                return Err("Arrival times are only saved for single-fire runs".into());
+            }
+            if config.simulations > 1 && config.replay.is_none() && !path.contains("{run}") {
+                return Err("Saving arrival times of several runs needs '{run}' in the file name".into());
+            }
+        }
+
+        if let Some(scaling) = &config.scaling {
+            if scaling.sizes.is_empty() {
+                return Err("--densities needs the --sizes of the scaling study".into());
+            }
+            if let Some(option) = grid_option {
+                return Err(format!("{} cannot be combined with the scaling study; use --sizes and --densities", option));
+            }
+            if config.model != Model::SingleFire || config.sweep.is_some() {
+                return Err("The scaling study runs single fires and cannot be combined with a sweep".into());
+            }
+            if config.map.is_some() || matches!(config.terrain, TerrainSource::Heightmap(_)) {
+                return Err("The scaling study cannot resize a map or heightmap".into());
+            }
+            if config.snapshots.is_some() || config.recording.is_some() || config.arrival.is_some() || config.replay.is_some() {
+                return Err("The scaling study saves no snapshots, recordings, arrival times or replays".into());
+            }
+        }
+
+        if let Some(index) = config.replay {
+            if config.seed.is_none() {
+                return Err("--replay requires --seed".into());
+            }
+            let fewest = match config.sweep.as_ref().and_then(|s| s.axis("simulations")) {
+                Some(Axis::Simulations(counts)) => counts.iter().copied().min().unwrap_or(config.simulations),
+                _ => config.simulations,
+            };
+            if index >= fewest {
+                return Err(format!(
+                    "Replay index {} out of range for {} simulations", index, fewest
+                ));
+            }
+        }
+
         Ok(config)
     }
 }
 
+impl Config {
+    /// The configured wind, or the default wind when another pattern is selected.
+    fn wind(&self) -> WindStrategy {
+        match self.burn_pattern {
+            BurnPattern::Wind(wind) => wind,
+            _ => WindStrategy::default(),
+        }
+    }
+
+    /// The configured Drossel–Schwabl parameters, or the defaults under another model.
+    fn drossel_schwabl(&self) -> DrosselSchwabl {
+        match self.model {
+            Model::DrosselSchwabl(model) => model,
+            _ => DrosselSchwabl::default(),
+        }
+    }
+
+    /// The configured season regime, or the default one under another model.
+    fn seasons(&self) -> Seasons {
+        match self.model {
+            Model::Seasons(model) => model,
+            _ => Seasons::default(),
+        }
+    }
+
+    /// The configured threshold search, or the default one under another model.
+    fn threshold(&self) -> Threshold {
+        match self.model {
+            Model::Threshold(model) => model,
+            _ => Threshold::default(),
+        }
+    }
+
+    /// The configured recording, or the default one.
+    fn recording(&self) -> Recording {
+        self.recording.clone().unwrap_or_default()
+    }
+
+    /// The configured scaling study, or the default one.
+    fn scaling(&self) -> Scaling {
+        self.scaling.clone().unwrap_or_default()
+    }
+
+    /// The configured noise placement, or the default one.
+    fn noise(&self) -> Placement {
+        match self.placement {
+            Placement::Noise { .. } => self.placement,
+            _ => Placement::Noise { scale: 8.0, octaves: 4 },
+        }
+    }
+
+    /// The configured Poisson cluster placement, or the default one.
+    fn poisson(&self) -> Placement {
+        match self.placement {
+            Placement::Poisson { .. } => self.placement,
+            _ => Placement::Poisson { parents: 0.005, radius: 3.0 },
+        }
+    }
+
+    /// The configured Hurst exponent, or the default one.
+    fn hurst(&self) -> f64 {
+        match self.placement {
+            Placement::Hurst { hurst } => hurst,
+            _ => 0.7,
+        }
+    }
+
+    /// The configured diamond-square roughness, or the default one.
+    fn roughness(&self) -> f64 {
+        match self.terrain {
+            TerrainSource::DiamondSquare { roughness } => roughness,
+            _ => 0.5,
+        }
+    }
+}
+
+/// Parses a grid size given as "<n>" (square) or "<width>x<height>".
+pub(crate) fn parse_size(value: &str) -> Result<(usize, usize), String> {
+    let parse = |v: &str| v.trim().parse::<usize>().map_err(|_| "Invalid value for size".to_string());
+    match value.split_once(['x', 'X']) {
+        Some((width, height)) => Ok((parse(width)?, parse(height)?)),
+        None => {
+            let side = parse(value)?;
+            Ok((side, side))
+        }
+    }
+}
+
+/// Parses a spread phase "<first>-<last>" of burn steps counted from 1,
+/// or a single step "<n>", into a 0-based half-open range.
+fn parse_phase(value: &str) -> Option<(u16, u16)> {
+    let parse = |v: &str| v.trim().parse::<u16>().ok();
+    let (first, last) = match value.split_once('-') {
+        Some((first, last)) => (parse(first)?, parse(last)?),
+        None => (parse(value)?, parse(value)?),
+    };
+    (first >= 1 && last >= first).then(|| (first - 1, last))
+}
+
+/// Parses a per-class option: a bare value applies to every fuel class,
+/// otherwise "<class>=<value>,..." sets the listed classes.
+fn parse_per_class<T: Copy>(
+    spec: &str,
+    arg_name: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<Vec<(Vegetation, T)>, String> {
+    let invalid = || format!("Invalid value for {}", arg_name);
+    if !spec.contains('=') {
+        let value = parse(spec.trim()).ok_or_else(invalid)?;
+        return Ok(Vegetation::ALL.into_iter().filter(|v| v.is_fuel()).map(|v| (v, value)).collect());
+    }
+    spec.split(',')
+        .map(|part| {
+            let (name, value) = part.split_once('=').ok_or_else(invalid)?;
+            let class = Vegetation::parse(name)
+                .filter(|v| v.is_fuel())
+                .ok_or_else(|| format!("Unknown fuel class '{}' for {}", name, arg_name))?;
+            Ok((class, parse(value.trim()).ok_or_else(invalid)?))
+        })
+        .collect()
+}
+
 /// Helper function: takes an iterator over arguments and a name for error messages.
 ///
 /// It advances the iterator to get the next element and attempts to parse it into T.
@@ -134,6 +968,7 @@ fn parse_arg<T: std::str::FromStr>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::forest::Fuel;
 
     fn mock_args(args: &[&str]) -> Vec<String> {
         std::iter::once("program_name".to_string())
@@ -145,11 +980,13 @@ mod tests {
     fn test_default_config() {
         let args = mock_args(&[]);
         let config = Config::new(&args).unwrap();
-        assert_eq!(config.size, 50);
+        assert_eq!(config.width, 20);
+        assert_eq!(config.height, 20);
         assert_eq!(config.density, 0.6);
-        assert_eq!(config.simulations, 100);
+        assert_eq!(config.simulations, 1);
         assert!(matches!(config.burn_pattern, BurnPattern::Moore(MooreNeighborhood)));
-        assert!(!config.graphics);
+        assert!(config.graphics);
+        assert_eq!(config.seed, None);
     }
 
     #[test]
@@ -159,15 +996,26 @@ mod tests {
             "-d", "0.7",
             "-c", "500",
             "-b", "vonneumann",
-            "-g"
+            "--boundary", "periodic",
+            "-p", "0.35",
+            "-g-off",
+            "--seed", "42",
+            "-t", "8",
+            "-o", "json",
         ]);
         let config = Config::new(&args).unwrap();
 
-        assert_eq!(config.size, 100);
+        assert_eq!(config.width, 100);
+        assert_eq!(config.height, 100);
         assert!((config.density - 0.7).abs() < f64::EPSILON);
         assert_eq!(config.simulations, 500);
         assert!(matches!(config.burn_pattern, BurnPattern::VonNeumann(VonNeumannNeighborhood)));
-        assert!(config.graphics);
+        assert_eq!(config.boundary, Boundary::Periodic);
+        assert_eq!(config.spread_prob, 0.35);
+        assert!(!config.graphics);
+        assert_eq!(config.seed, Some(42));
+        assert_eq!(config.threads, 8);
+        assert_eq!(config.output_format, OutputFormat::Json);
     }
 
     #[test]
@@ -175,5 +1023,296 @@ mod tests {
         let args = mock_args(&["-d", "1.5"]);
         let result = Config::new(&args);
         assert!(result.is_err());
+        assert!(Config::new(&mock_args(&["--spread-prob", "-0.1"])).is_err());
+    }
+
+    #[test]
+    fn test_wind_options() {
+        let config = Config::new(&mock_args(&["--wind-dir", "SW", "--wind-strength", "2.5", "-b", "wind"])).unwrap();
+        let BurnPattern::Wind(wind) = config.burn_pattern else { panic!("expected wind") };
+        assert_eq!(wind, WindStrategy { direction: 225.0, strength: 2.5, reach: 1 });
+
+        let config = Config::new(&mock_args(&["-b", "wind", "--wind-reach", "2"])).unwrap();
+        assert!(matches!(config.burn_pattern, BurnPattern::Wind(WindStrategy { reach: 2, .. })));
+
+        assert!(Config::new(&mock_args(&["--wind-dir", "up"])).is_err());
+        // neither order lets a wind option and another pattern silently override each other
+        assert!(Config::new(&mock_args(&["--wind-strength", "2", "-b", "moore"])).is_err());
+        assert!(Config::new(&mock_args(&["-b", "vonneumann", "--wind-dir", "N"])).is_err());
+        assert!(Config::new(&mock_args(&["--wind-reach", "3"])).is_err());
+    }
+
+    #[test]
+    fn test_rectangular_size() {
+        let config = Config::new(&mock_args(&["--size", "400x50"])).unwrap();
+        assert_eq!((config.width, config.height), (400, 50));
+
+        let config = Config::new(&mock_args(&["-s", "30", "--height", "5"])).unwrap();
+        assert_eq!((config.width, config.height), (30, 5));
+
+        assert!(Config::new(&mock_args(&["-s", "400x"])).is_err());
+        assert!(Config::new(&mock_args(&["-s", "0x10"])).is_err());
+    }
+
+    #[test]
+    fn test_invalid_output_format() {
+        assert!(Config::new(&mock_args(&["-o", "xml"])).is_err());
+        let config = Config::new(&mock_args(&["--output-format", "CSV"])).unwrap();
+        assert_eq!(config.output_format, OutputFormat::Csv);
+    }
+
+    #[test]
+    fn test_replay_needs_seed() {
+        assert!(Config::new(&mock_args(&["-c", "10", "--replay", "3"])).is_err());
+        assert!(Config::new(&mock_args(&["-c", "10", "--seed", "1", "--replay", "10"])).is_err());
+
+        let config = Config::new(&mock_args(&["-c", "10", "--seed", "1", "--replay", "3"])).unwrap();
+        assert_eq!(config.replay, Some(3));
+    }
+
+    #[test]
+    fn test_terrain_options() {
+        assert!(matches!(Config::new(&mock_args(&[])).unwrap().terrain, TerrainSource::Flat));
+
+        let config = Config::new(&mock_args(&["--terrain", "diamond-square", "--relief", "20"])).unwrap();
+        assert!(matches!(config.terrain, TerrainSource::DiamondSquare { roughness: 0.5 }));
+        assert_eq!(config.relief, 20.0);
+
+        let config = Config::new(&mock_args(&["--roughness", "0.8", "--terrain", "diamond-square"])).unwrap();
+        assert!(matches!(config.terrain, TerrainSource::DiamondSquare { roughness: 0.8 }));
+
+        assert!(Config::new(&mock_args(&["--terrain", "perlin"])).is_err());
+        assert!(Config::new(&mock_args(&["--roughness", "2"])).is_err());
+        assert!(Config::new(&mock_args(&["--heightmap", "/nonexistent/map.pgm"])).is_err());
+    }
+
+    #[test]
+    fn test_heightmap_sets_size() {
+        let path = std::env::temp_dir().join(format!("forest_heightmap_{}.txt", std::process::id()));
+        std::fs::write(&path, "0 1 2\n1 2 3\n").unwrap();
+        let file = path.to_str().unwrap();
+
+        let config = Config::new(&mock_args(&["--heightmap", file])).unwrap();
+        assert_eq!((config.width, config.height), (3, 2));
+        assert!(matches!(config.terrain, TerrainSource::Heightmap(_)));
+        assert!(Config::new(&mock_args(&["--heightmap", file, "-s", "10"])).is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_fuel_options() {
+        let defaults = VegetationTable::default();
+        assert_eq!(Config::new(&mock_args(&[])).unwrap().classes, defaults);
+
+        let config = Config::new(&mock_args(&["--burn-steps", "5", "--spread-phase", "2-3"])).unwrap();
+        assert_eq!(config.classes[Vegetation::Grass].fuel, Fuel { burn_steps: 5, spread_from: 1, spread_until: 3 });
+        assert_eq!(config.classes[Vegetation::Deciduous].fuel, Fuel { burn_steps: 5, spread_from: 1, spread_until: 3 });
+
+        let config = Config::new(&mock_args(&["--burn-steps", "conifer=4", "--spread-phase", "conifer=4"])).unwrap();
+        let conifer = config.classes[Vegetation::Conifer].fuel;
+        assert_eq!((conifer.burn_steps, conifer.spread_from, conifer.spread_until), (4, 3, 4));
+        assert_eq!(config.classes[Vegetation::Shrub], defaults[Vegetation::Shrub]);
+
+        assert!(Config::new(&mock_args(&["--burn-steps", "0"])).is_err());
+        assert!(Config::new(&mock_args(&["--spread-phase", "3-2"])).is_err());
+        assert!(Config::new(&mock_args(&["--burn-steps", "2", "--spread-phase", "3-4"])).is_err());
+        assert!(Config::new(&mock_args(&["--burn-steps", "water=2"])).is_err());
+    }
+
+    #[test]
+    fn test_input_map_sets_size_and_density() {
+        let path = std::env::temp_dir().join(format!("forest_map_{}.txt", std::process::id()));
+        std::fs::write(&path, "TT..\nGG~~\n").unwrap();
+        let file = path.to_str().unwrap();
+
+        let config = Config::new(&mock_args(&["--input-map", file])).unwrap();
+        assert_eq!((config.width, config.height), (4, 2));
+        assert_eq!(config.density, 0.5);
+        assert_eq!(config.vegetation.densities(config.density).last(), Some(&(Vegetation::Water, 0.25)));
+        assert!(Config::new(&mock_args(&["--input-map", file, "-s", "10"])).is_err());
+        assert!(Config::new(&mock_args(&["--input-map", file, "-a"])).is_err());
+        for option in [&["-d", "0.9"][..], &["--vegetation", "grass=0.5"], &["--placement", "noise"], &["--hurst", "0.7"]] {
+            assert!(Config::new(&mock_args(&[&["--input-map", file][..], option].concat())).is_err());
+        }
+        assert!(Config::new(&mock_args(&["--input-map", "/nonexistent/stand.txt"])).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_options() {
+        let config = Config::new(&mock_args(&["--snapshot", "scar_{step}.png"])).unwrap();
+        let snapshots = config.snapshots.unwrap();
+        assert_eq!(snapshots.path, "scar_{step}.png");
+        assert_eq!(snapshots.steps, vec![SnapshotStep::Initial, SnapshotStep::Final]);
+
+        let config = Config::new(&mock_args(&["--snapshot-steps", "5,final", "--snapshot", "{run}-{step}.bin", "-c", "4"])).unwrap();
+        assert_eq!(config.snapshots.unwrap().steps, vec![SnapshotStep::Step(5), SnapshotStep::Final]);
+        let config = Config::new(&mock_args(&["--snapshot", "scar.txt", "--snapshot-steps", "final", "-c", "4", "--seed", "1", "--replay", "2"]));
+        assert!(config.is_ok());
+
+        assert!(Config::new(&mock_args(&["--snapshot", "scar.png"])).is_err());
+        assert!(Config::new(&mock_args(&["--snapshot", "{step}.png", "-c", "2"])).is_err());
+        assert!(Config::new(&mock_args(&["--snapshot-steps", "final"])).is_err());
+        assert!(Config::new(&mock_args(&["--snapshot", "s{step}.png", "--snapshot-steps", "last"])).is_err());
+    }
+
+    #[test]
+    fn test_record_options() {
+        let config = Config::new(&mock_args(&["--record-scale", "5", "--record", "fire.gif", "--record-frames", "80"])).unwrap();
+        assert_eq!(
+            config.recording,
+            Some(Recording { path: "fire.gif".into(), delay_ms: 100, scale: 5, max_frames: 80 })
+        );
+        assert!(Config::new(&mock_args(&["--record", "fire_{run}.apng", "-c", "3"])).is_ok());
+
+        assert!(Config::new(&mock_args(&["--record-delay", "50"])).is_err());
+        assert!(Config::new(&mock_args(&["--record", "fire.mp4"])).is_err());
+        assert!(Config::new(&mock_args(&["--record", "fire.gif", "-c", "3"])).is_err());
+        assert!(Config::new(&mock_args(&["--record", "fire.gif", "--record-scale", "0"])).is_err());
+        assert!(Config::new(&mock_args(&["--record", "fire.gif", "--model", "ds"])).is_err());
+    }
+
+    #[test]
+    fn test_arrival_option() {
+        let config = Config::new(&mock_args(&["--arrival", "arrival.csv"])).unwrap();
+        assert_eq!(config.arrival.as_deref(), Some("arrival.csv"));
+        assert!(Config::new(&mock_args(&["--arrival", "arrival_{run}.png", "-c", "3"])).is_ok());
+
+        assert!(Config::new(&mock_args(&["--arrival", "arrival.txt"])).is_err());
+        assert!(Config::new(&mock_args(&["--arrival", "arrival.png", "-c", "3"])).is_err());
+        assert!(Config::new(&mock_args(&["--arrival", "arrival.csv", "--model", "ds"])).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_placement_options() {
+        assert_eq!(Config::new(&mock_args(&[])).unwrap().placement, Placement::Uniform);
+        let config = Config::new(&mock_args(&["--placement", "noise"])).unwrap();
+        assert_eq!(config.placement, Placement::Noise { scale: 8.0, octaves: 4 });
+        let config = Config::new(&mock_args(&["--octaves", "2", "--noise-scale", "12"])).unwrap();
+        assert_eq!(config.placement, Placement::Noise { scale: 12.0, octaves: 2 });
+        let config = Config::new(&mock_args(&["--cluster-radius", "1.5", "--placement", "poisson"])).unwrap();
+        assert_eq!(config.placement, Placement::Poisson { parents: 0.005, radius: 1.5 });
+        let config = Config::new(&mock_args(&["--hurst", "0.3"])).unwrap();
+        assert_eq!(config.placement, Placement::Hurst { hurst: 0.3 });
+
+        assert!(Config::new(&mock_args(&["--placement", "perlin"])).is_err());
+        assert!(Config::new(&mock_args(&["--octaves", "0"])).is_err());
+        assert!(Config::new(&mock_args(&["--parents", "0"])).is_err());
+        assert!(Config::new(&mock_args(&["--hurst", "1.5"])).is_err());
+    }
+
+    #[test]
+    fn test_vegetation_options() {
+        let config = Config::new(&mock_args(&["--vegetation", "grass=0.3,deciduous=0.2,rock=0.1"])).unwrap();
+        assert!((config.density - 0.5).abs() < 1e-12);
+        assert_eq!(config.vegetation.densities(config.density).len(), 3);
+
+        // a later density rescales the tree classes only
+        let config = Config::new(&mock_args(&["--vegetation", "grass=0.3,rock=0.1", "-d", "0.9"])).unwrap();
+        assert_eq!(config.density, 0.9);
+        assert!(Config::new(&mock_args(&["--vegetation", "grass=0.3,rock=0.1", "-d", "0.95"])).is_err());
+
+        let config = Config::new(&mock_args(&["--ignition", "grass=0.9,shrub=0.1"])).unwrap();
+        assert_eq!(config.classes[Vegetation::Grass].ignition, 0.9);
+        assert_eq!(config.classes[Vegetation::Shrub].ignition, 0.1);
+        assert!(Config::new(&mock_args(&["--ignition", "1.5"])).is_err());
+        assert!(Config::new(&mock_args(&["--vegetation", "lava=0.1"])).is_err());
+    }
+
+    #[test]
+    fn test_drossel_schwabl_options() {
+        assert_eq!(Config::new(&mock_args(&[])).unwrap().model, Model::SingleFire);
+
+        let config = Config::new(&mock_args(&["--model", "ds", "-g-off"])).unwrap();
+        assert_eq!(config.model, Model::DrosselSchwabl(DrosselSchwabl::default()));
+
+        let config = Config::new(&mock_args(&["--growth", "0.05", "--lightning", "0.001", "--ds-steps", "50", "-g-off"])).unwrap();
+        let Model::DrosselSchwabl(model) = config.model else { panic!("expected Drossel–Schwabl") };
+        assert_eq!(model, DrosselSchwabl { growth: 0.05, lightning: 0.001, steps: 50, warmup: 200 });
+
+        assert!(Config::new(&mock_args(&["--ds-steps", "0", "-g-off"])).is_err());
+        assert!(Config::new(&mock_args(&["--ds-steps", "0"])).is_ok());
+        assert!(Config::new(&mock_args(&["--lightning", "2"])).is_err());
+        assert!(Config::new(&mock_args(&["--model", "ds", "-a"])).is_err());
+    }
+
+    #[test]
+    fn test_threshold_options() {
+        let config = Config::new(&mock_args(&["--model", "threshold"])).unwrap();
+        assert_eq!(config.model, Model::Threshold(Threshold::default()));
+        assert_eq!(config.threshold().criterion, Criterion::Percolation);
+
+        let config = Config::new(&mock_args(&["--criterion", "burned", "--tolerance", "0.01", "--target", "0.3"])).unwrap();
+        assert_eq!(config.model, Model::Threshold(Threshold { criterion: Criterion::Burned, target: 0.3, tolerance: 0.01 }));
+
+        assert!(Config::new(&mock_args(&["--target", "1.0"])).is_err());
+        assert!(Config::new(&mock_args(&["--criterion", "cover"])).is_err());
+        assert!(Config::new(&mock_args(&["--model", "threshold", "-a"])).is_err());
+        assert!(Config::new(&mock_args(&["--model", "threshold", "--input-map", "maps/river.txt"])).is_err());
+        assert!(Config::new(&mock_args(&["--model", "ds", "--input-map", "maps/river.txt"])).is_err());
+        assert!(Config::new(&mock_args(&["--seasons", "3", "--input-map", "maps/river.txt"])).is_err());
+    }
+
+    #[test]
+    fn test_scaling_options() {
+        let config = Config::new(&mock_args(&["--sizes", "64,16,32", "--densities", "0.5:0.7:5"])).unwrap();
+        let scaling = config.scaling.unwrap();
+        assert_eq!(scaling.sizes, vec![16, 32, 64]);
+        assert_eq!(scaling.densities.len(), 5);
+        assert_eq!(scaling.densities[4], 0.7);
+        assert_eq!(Config::new(&mock_args(&["--sizes", "8,16"])).unwrap().scaling.unwrap().densities.len(), 41);
+
+        assert!(Config::new(&mock_args(&["--sizes", "16"])).is_err());
+        assert!(Config::new(&mock_args(&["--sizes", "16,x"])).is_err());
+        assert!(Config::new(&mock_args(&["--densities", "0.5,0.6"])).is_err());
+        assert!(Config::new(&mock_args(&["--sizes", "8,16", "-a"])).is_err());
+        assert!(Config::new(&mock_args(&["--sizes", "8,16", "--model", "threshold"])).is_err());
+        assert!(Config::new(&mock_args(&["--sizes", "8,16", "--arrival", "a.csv"])).is_err());
+        // --sizes and --densities replace the grid size and the density
+        assert!(Config::new(&mock_args(&["--sizes", "8,16", "-s", "32"])).is_err());
+        assert!(Config::new(&mock_args(&["-H", "10", "--sizes", "8,16"])).is_err());
+        assert!(Config::new(&mock_args(&["--sizes", "8,16", "-d", "0.6"])).is_err());
+    }
+
+    #[test]
+    fn test_sweep_options() {
+        let config = Config::new(&mock_args(&["-a"])).unwrap();
+        let sweep = config.sweep.unwrap();
+        assert!(matches!(sweep.axis("density"), Some(Axis::Density(d)) if d.len() == 20 && d[19] == 1.0));
+
+        let args = ["--sweep", "burn-pattern=moore,wind", "-ss", "0.1", "--sweep", "size=16,32", "--wind-dir", "N"];
+        let sweep = Config::new(&mock_args(&args)).unwrap().sweep.unwrap();
+        assert_eq!(sweep.points(), 2 * 10 * 2);
+        assert!(matches!(
+            sweep.axis("burn-pattern"),
+            Some(Axis::BurnPattern(p)) if matches!(p[1], BurnPattern::Wind(w) if w.direction == WindStrategy::parse_direction("N").unwrap())
+        ));
+
+        assert!(Config::new(&mock_args(&["--sweep", "spread-prob=0.5"])).is_err());
+        assert!(Config::new(&mock_args(&["--sweep", "density=0.5,0.9", "--vegetation", "water=0.2"])).is_err());
+        assert!(Config::new(&mock_args(&["--sweep", "simulations=5,20", "--seed", "1", "--replay", "7"])).is_err());
+        assert!(Config::new(&mock_args(&["--sweep", "density=0.5", "--model", "seasons"])).is_err());
+    }
+
+    #[test]
+    fn test_season_options() {
+        let config = Config::new(&mock_args(&["--model", "seasons"])).unwrap();
+        assert_eq!(config.model, Model::Seasons(Seasons::default()));
+
+        let config = Config::new(&mock_args(&["--seasons", "30", "--recovery", "age", "--recovery-years", "5"])).unwrap();
+        assert_eq!(config.model, Model::Seasons(Seasons { seasons: 30, years: 5, recovery: Recovery::Age { maturity: 20 } }));
+
+        let config = Config::new(&mock_args(&["--maturity", "8", "--recovery", "age"])).unwrap();
+        let Model::Seasons(model) = config.model else { panic!("expected seasons") };
+        assert_eq!(model.recovery, Recovery::Age { maturity: 8 });
+
+        let config = Config::new(&mock_args(&["--regrowth-rate", "0.2"])).unwrap();
+        let Model::Seasons(model) = config.model else { panic!("expected seasons") };
+        assert_eq!(model.recovery, Recovery::Logistic { rate: 0.2 });
+
+        assert!(Config::new(&mock_args(&["--recovery", "instant"])).is_err());
+        assert!(Config::new(&mock_args(&["--maturity", "0"])).is_err());
+        assert!(Config::new(&mock_args(&["--seasons", "3", "-a"])).is_err());
+    }
+}
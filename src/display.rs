@@ -1,22 +1,42 @@
-use std::io::{stdout, Stdout, Write};
-use crate::forest::{CellState, Forest};
+use std::io::{stdout, BufWriter, Stdout, Write};
+use crate::forest::Forest;
 use crossterm::{
     cursor::{Hide, MoveTo, Show},
-    execute,
+    execute, queue,
+    style::{Color, ResetColor, SetBackgroundColor},
     terminal::{Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
 };
 
 pub struct ForestDisplay {
     out: Stdout,
+    caption: Option<String>, // drawn above the grid, e.g. the wind direction
+    shade_elevation: bool,   // tint each cell by the terrain height under it
+}
+
+impl Default for ForestDisplay {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ForestDisplay {
     pub fn new() -> Self {
         ForestDisplay {
             out: stdout(),
+            caption: None,
+            shade_elevation: false,
         }
     }
 
+    pub fn set_caption(&mut self, caption: impl Into<String>) {
+        self.caption = Some(caption.into());
+    }
+
+    /// Shades cells from green lowlands to white peaks when the forest has terrain.
+    pub fn set_shade_elevation(&mut self, shade: bool) {
+        self.shade_elevation = shade;
+    }
+
     pub fn prepare_animation(&mut self) {
         // clear_screen();
         execute!(self.out, EnterAlternateScreen, Hide).unwrap();
@@ -24,27 +44,31 @@ impl ForestDisplay {
 
     pub fn render_frame(&mut self, forest: &Forest) {
 
-        // let mut out = stdout();
-        // clear_screen();
-        execute!(self.out, Clear(ClearType::All), MoveTo(0, 0)).unwrap();
+        // stdout flushes at every newline; buffer the whole frame and flush it once
+        let mut out = BufWriter::new(self.out.lock());
+        queue!(out, Clear(ClearType::All), MoveTo(0, 0)).unwrap();
+
+        if let Some(caption) = &self.caption {
+            writeln!(out, "{}", caption).unwrap();
+        }
+
+        let terrain = forest.terrain().filter(|_| self.shade_elevation);
 
         // draw the grid
-        for row in &forest.grid {
-            for cell in row {
-                let symbol = match cell {
-                    CellState::Empty   => "  ",
-                    CellState::Tree    => "🌲", // note the space
-                    CellState::Burning => "🔥",
-                    CellState::Burned  => "◼️",
-                    // CellState::Lightning => "⚡️",
-                };
-
-                write!(self.out, "{}", symbol).unwrap(); // ?;
+        for y in 0..forest.height {
+            for x in 0..forest.width {
+                if let Some(terrain) = terrain {
+                    queue!(out, SetBackgroundColor(elevation_color(terrain.get(x, y)))).unwrap();
+                }
+                write!(out, "{}", forest.glyph(forest.index(x, y))).unwrap(); // ?;
+            }
+            if terrain.is_some() {
+                queue!(out, ResetColor).unwrap();
             }
-            writeln!(self.out).unwrap(); // ?;
+            writeln!(out).unwrap(); // ?;
         }
 
-        self.out.flush().unwrap(); // Ensure immediate flush
+        out.flush().unwrap(); // Ensure immediate flush
         // Ok();
     }
 
@@ -54,3 +78,17 @@ impl ForestDisplay {
 
 }
 
+/// Hypsometric tint for a normalized elevation: green valleys, brown slopes, white peaks.
+fn elevation_color(elevation: f64) -> Color {
+    const STOPS: [(f64, [f64; 3]); 3] = [
+        (0.0, [40.0, 90.0, 40.0]),
+        (0.6, [130.0, 105.0, 70.0]),
+        (1.0, [235.0, 235.0, 235.0]),
+    ];
+    let e = elevation.clamp(0.0, 1.0);
+    let upper = STOPS.iter().position(|&(h, _)| h >= e).unwrap_or(STOPS.len() - 1).max(1);
+    let ((h0, c0), (h1, c1)) = (STOPS[upper - 1], STOPS[upper]);
+    let t = (e - h0) / (h1 - h0);
+    let [r, g, b] = [0, 1, 2].map(|i| (c0[i] + (c1[i] - c0[i]) * t).round() as u8);
+    Color::Rgb { r, g, b }
+}
@@ -0,0 +1,269 @@
+use std::sync::Arc;
+use crate::config::Config;
+use crate::display::ForestDisplay;
+use crate::fire_spread::FireSpreadStrategy;
+use crate::simulation::{build_forest, build_terrain, rng_from_seed, run_batch, seed_for_run, with_strategy};
+use crate::terrain::Terrain;
+use rand::Rng;
+
+/// Parameters of the Drossel–Schwabl self-organized forest-fire model.
+///
+/// Every step empty cells grow a tree with probability `growth` (p) and every tree is
+/// struck by lightning with probability `lightning` (f). Fires burn out within the step
+/// they start in, the usual separation of time scales f ≪ p, and their scars clear
+/// to empty ground at the start of the next step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DrosselSchwabl {
+    pub growth: f64,
+    pub lightning: f64,
+    pub steps: usize,  // recorded steps per run; 0 animates until interrupted
+    pub warmup: usize, // steps run before recording, to reach the steady state
+}
+
+impl Default for DrosselSchwabl {
+    fn default() -> Self {
+        DrosselSchwabl { growth: 0.01, lightning: 0.0001, steps: 1000, warmup: 200 }
+    }
+}
+
+/// Fires recorded over every run of a batch.
+#[derive(Debug)]
+pub struct FireSizeResults {
+    pub seed: u64,               // master seed the batch was run with
+    pub sizes: Vec<usize>,       // trees burned by each fire, sorted
+    pub steps: usize,            // recorded steps over all runs
+    pub mean_density: f64,       // trees per cell, averaged over the recorded steps
+    pub histogram: Vec<SizeBin>, // logarithmic bins of the fire sizes
+    pub tau: f64,                // power-law exponent fitted to the histogram, NaN if too few bins
+}
+
+/// Fires with sizes in `min..=max`. `frequency` is the count per unit of size
+/// and per fire, the estimate of n(s) that follows s^-τ for a power law.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeBin {
+    pub min: usize,
+    pub max: usize,
+    pub count: usize,
+    pub frequency: f64,
+}
+
+impl FireSizeResults {
+    pub fn fires(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Mean trees burned per fire, None when lightning never struck a tree.
+    pub fn mean_size(&self) -> Option<f64> {
+        (!self.sizes.is_empty()).then(|| self.sizes.iter().sum::<usize>() as f64 / self.sizes.len() as f64)
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.sizes.last().copied().unwrap_or(0)
+    }
+}
+
+/// What one run recorded.
+struct RunRecording {
+    sizes: Vec<usize>,
+    steps: usize,
+    density_sum: f64,
+}
+
+pub fn run_drossel_schwabl(config: &Config, model: DrosselSchwabl) -> FireSizeResults {
+    let master_seed = config.seed.unwrap_or_else(|| rand::rng().random());
+    let terrain = build_terrain(config, master_seed);
+    let terrain = terrain.as_ref();
+
+    let recordings = run_batch(config, |index| {
+        with_strategy(config, terrain, |strategy| evolve(config, model, terrain, strategy, master_seed, index))
+    });
+
+    let mut sizes: Vec<usize> = recordings.iter().flat_map(|r| r.sizes.iter().copied()).collect();
+    sizes.sort_unstable();
+    let steps = recordings.iter().map(|r| r.steps).sum();
+    let density_sum: f64 = recordings.iter().map(|r| r.density_sum).sum();
+
+    let histogram = log_histogram(&sizes);
+    let tau = fit_exponent(&histogram);
+    FireSizeResults {
+        seed: master_seed,
+        sizes,
+        steps,
+        mean_density: density_sum / steps as f64,
+        histogram,
+        tau,
+    }
+}
+
+/// Evolves one forest for the warm-up and the recorded steps.
+fn evolve(
+    config: &Config,
+    model: DrosselSchwabl,
+    terrain: Option<&Arc<Terrain>>,
+    strategy: &dyn FireSpreadStrategy,
+    master_seed: u64,
+    index: usize,
+) -> RunRecording {
+    let mut rng = rng_from_seed(seed_for_run(master_seed, index));
+    let mut forest = build_forest(config, terrain, &mut rng);
+    let cells = (forest.width * forest.height) as f64;
+    let mut trees = forest.total_trees;
+
+    let mut display = config.graphics.then(|| {
+        let mut display = ForestDisplay::new();
+        display.set_shade_elevation(config.shade_elevation);
+        display.prepare_animation();
+        display
+    });
+
+    let mut recording = RunRecording { sizes: Vec::new(), steps: 0, density_sum: 0.0 };
+    let mut scarred = false;
+    let mut step = 0;
+    while model.steps == 0 || step < model.warmup + model.steps {
+        if scarred {
+            forest.clear_burned();
+            scarred = false;
+        }
+        trees += forest.regrow(model.growth, &mut rng);
+
+        for strike in forest.lightning_strikes(model.lightning, &mut rng) {
+            // an earlier fire of this step may have taken the tree already
+            let before = forest.burned_count;
+            if !forest.ignite(Some(strike), &mut rng) {
+                continue;
+            }
+            while !forest.fire_spread(strategy, &mut rng) {}
+            let size = forest.burned_count - before;
+            trees -= size;
+            scarred = true;
+            if step >= model.warmup {
+                recording.sizes.push(size);
+            }
+        }
+
+        if step >= model.warmup {
+            recording.steps += 1;
+            recording.density_sum += trees as f64 / cells;
+        }
+
+        if let Some(display) = display.as_mut() {
+            display.set_caption(format!(
+                "Drossel–Schwabl step {}: density {:.3}, {} fires recorded",
+                step, trees as f64 / cells, recording.sizes.len()
+            ));
+            display.render_frame(&forest);
+            std::thread::sleep(std::time::Duration::from_millis(config.frame_delay_ms));
+        }
+        step += 1;
+    }
+
+    if let Some(mut display) = display {
+        display.tidy_up();
+    }
+    recording
+}
+
+/// Bins sorted fire sizes by powers of two: 1, 2-3, 4-7, ...
+fn log_histogram(sorted: &[usize]) -> Vec<SizeBin> {
+    let Some(&largest) = sorted.last() else {
+        return Vec::new();
+    };
+    let fires = sorted.len() as f64;
+
+    let mut bins = Vec::new();
+    let mut min = 1;
+    let mut start = 0;
+    while min <= largest {
+        let max = 2 * min - 1;
+        let end = start + sorted[start..].partition_point(|&s| s <= max);
+        let count = end - start;
+        bins.push(SizeBin { min, max, count, frequency: count as f64 / (max - min + 1) as f64 / fires });
+        start = end;
+        min *= 2;
+    }
+    bins
+}
+
+/// Least-squares slope of log n(s) against log s over the non-empty bins, negated.
+fn fit_exponent(bins: &[SizeBin]) -> f64 {
+    let points: Vec<(f64, f64)> = bins
+        .iter()
+        .filter(|bin| bin.count > 0)
+        .map(|bin| (((bin.min * bin.max) as f64).sqrt().ln(), bin.frequency.ln()))
+        .collect();
+    if points.len() < 2 {
+        return f64::NAN;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let variance: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    -covariance / variance
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ds_config() -> Config {
+        Config {
+            width: 64,
+            height: 64,
+            density: 0.0,
+            graphics: false,
+            seed: Some(17),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_histogram_bins_powers_of_two() {
+        let bins = log_histogram(&[1, 1, 2, 3, 3, 9]);
+        let counts: Vec<_> = bins.iter().map(|b| (b.min, b.max, b.count)).collect();
+        assert_eq!(counts, vec![(1, 1, 2), (2, 3, 3), (4, 7, 0), (8, 15, 1)]);
+        assert!((bins[1].frequency - 3.0 / 2.0 / 6.0).abs() < 1e-12);
+        assert!(log_histogram(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_fit_recovers_power_law() {
+        // exact n(s) = s^-2 at the bin centers
+        let bins: Vec<SizeBin> = (0..8)
+            .map(|k| {
+                let (min, max) = (1usize << k, (2usize << k) - 1);
+                SizeBin { min, max, count: 1, frequency: ((min * max) as f64).sqrt().powf(-2.0) }
+            })
+            .collect();
+        assert!((fit_exponent(&bins) - 2.0).abs() < 1e-9);
+        assert!(fit_exponent(&bins[..1]).is_nan());
+    }
+
+    #[test]
+    fn test_forest_self_organizes() {
+        let model = DrosselSchwabl { growth: 0.05, lightning: 0.001, steps: 400, warmup: 200 };
+        let results = run_drossel_schwabl(&ds_config(), model);
+
+        assert!(results.fires() > 50);
+        assert_eq!(results.steps, 400);
+        assert!(results.sizes.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(results.histogram.iter().map(|b| b.count).sum::<usize>(), results.fires());
+        // fires keep the forest well below full cover, in the range of the steady state
+        assert!(results.mean_density > 0.2 && results.mean_density < 0.7);
+        assert!(results.tau > 0.0);
+    }
+
+    #[test]
+    fn test_seeded_and_thread_invariant() {
+        let model = DrosselSchwabl { growth: 0.05, lightning: 0.001, steps: 100, warmup: 50 };
+        let config = Config { simulations: 4, ..ds_config() };
+
+        let single = run_drossel_schwabl(&config, model);
+        let parallel = run_drossel_schwabl(&Config { threads: 3, ..config.clone() }, model);
+        assert_eq!(single.sizes, parallel.sizes);
+        assert_eq!(single.mean_density, parallel.mean_density);
+        assert_eq!(single.steps, 400);
+    }
+}
@@ -1,7 +1,126 @@
 // src/fire_spread.rs
+use std::fmt;
+use crate::terrain::Terrain;
 
+/// What happens to neighbors that fall off the edge of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Boundary {
+    /// Hard edges: off-grid neighbors are dropped.
+    #[default]
+    Open,
+    /// Toroidal wrap-around: leaving on one side enters on the opposite one.
+    Periodic,
+}
+
+impl Boundary {
+    /// Name as accepted by `--boundary`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Boundary::Open => "open",
+            Boundary::Periodic => "periodic",
+        }
+    }
+
+    /// Maps coordinate `n` onto `0..len`, or `None` if it leaves an open grid.
+    #[inline]
+    fn resolve(&self, n: i64, len: usize) -> Option<usize> {
+        let len = len as i64;
+        if (0..len).contains(&n) {
+            return Some(n as usize);
+        }
+        match self {
+            Boundary::Open => None,
+            Boundary::Periodic => Some(n.rem_euclid(len) as usize),
+        }
+    }
+}
+
+/// Grid dimensions and edge behavior a strategy has to keep its neighbors within.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub width: usize,
+    pub height: usize,
+    pub boundary: Boundary,
+}
+
+impl Bounds {
+    /// Bounds with open (hard) edges.
+    pub fn new(width: usize, height: usize) -> Self {
+        Bounds { width, height, boundary: Boundary::Open }
+    }
+
+    pub fn with_boundary(self, boundary: Boundary) -> Self {
+        Bounds { boundary, ..self }
+    }
+
+    /// The cell `(dx, dy)` away from `(x, y)` under the boundary condition,
+    /// or `None` when it falls off an open grid.
+    #[inline]
+    pub fn offset(&self, x: usize, y: usize, dx: i32, dy: i32) -> Option<(usize, usize)> {
+        let nx = self.boundary.resolve(x as i64 + dx as i64, self.width)?;
+        let ny = self.boundary.resolve(y as i64 + dy as i64, self.height)?;
+        Some((nx, ny))
+    }
+}
+
+/// The neighbors of one cell resolved through [`Bounds::offset`], each at most once.
+/// On periodic grids two or fewer cells across, two offsets can land on the same cell,
+/// which must get only one ignition draw.
+struct Distinct {
+    bounds: Bounds,
+    cells: [(usize, usize); 12], // the most a strategy reaches: 8 adjacent cells and 3 jumps
+    len: usize,
+}
+
+impl Distinct {
+    fn new(bounds: Bounds) -> Self {
+        Distinct { bounds, cells: [(0, 0); 12], len: 0 }
+    }
+
+    /// The cell `(dx, dy)` away from `(x, y)`, or `None` when it is off an open grid
+    /// or was already handed out.
+    #[inline]
+    fn offset(&mut self, x: usize, y: usize, dx: i32, dy: i32) -> Option<(usize, usize)> {
+        let cell = self.bounds.offset(x, y, dx, dy)?;
+        // distinct offsets stay distinct cells on an open grid
+        if self.bounds.boundary == Boundary::Open {
+            return Some(cell);
+        }
+        if self.cells[..self.len].contains(&cell) {
+            return None;
+        }
+        self.cells[self.len] = cell;
+        self.len += 1;
+        Some(cell)
+    }
+}
+
+/// Decides which cells a burning cell can ignite.
+///
+/// Implementors only provide `for_each_neighbor`, which hands every in-grid
+/// neighbor to a callback, so the spread loop never allocates. Neighbors must be
+/// resolved through [`Bounds::offset`] so that they respect the boundary condition;
+/// the forest drops any coordinate still outside the grid. A neighbor should be
+/// handed out once even when two offsets wrap onto it, or it gets two ignition draws.
+/// Strategies written against the old `Vec`-returning `spread` can be wrapped
+/// in [`VecSpread`] unchanged, or move their body into `for_each_neighbor`
+/// and call `visit` where they used to `push`.
 pub trait FireSpreadStrategy {
-    fn spread(&self, x: usize, y: usize, size: usize) -> Vec<(usize, usize)>;
+    fn for_each_neighbor(&self, x: usize, y: usize, bounds: Bounds, visit: &mut dyn FnMut(usize, usize));
+
+    /// Like `for_each_neighbor`, with a weight that scales the spread probability
+    /// towards each neighbor. Isotropic strategies keep the default weight of 1.0.
+    fn for_each_weighted_neighbor(&self, x: usize, y: usize, bounds: Bounds, visit: &mut dyn FnMut(usize, usize, f64)) {
+        self.for_each_neighbor(x, y, bounds, &mut |nx, ny| visit(nx, ny, 1.0));
+    }
+
+    /// Neighbors collected into a `Vec`; convenient for tests, but allocates on every
+    /// call, so the simulation never uses it.
+    fn spread(&self, x: usize, y: usize, bounds: Bounds) -> Vec<(usize, usize)> {
+        let mut neighbors = Vec::new();
+        self.for_each_neighbor(x, y, bounds, &mut |nx, ny| neighbors.push((nx, ny)));
+        neighbors
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -10,25 +129,171 @@ pub struct MooreNeighborhood;
 pub struct VonNeumannNeighborhood;
 
 impl FireSpreadStrategy for MooreNeighborhood {
-    fn spread(&self, x: usize, y: usize, size: usize) -> Vec<(usize, usize)> {
-        let mut neighbors = Vec::new();
+    fn for_each_neighbor(&self, x: usize, y: usize, bounds: Bounds, visit: &mut dyn FnMut(usize, usize)) {
+        let mut neighbors = Distinct::new(bounds);
         for dx in -1..=1 {
             for dy in -1..=1 {
                 if dx == 0 && dy == 0 { continue; }
-                self.check_boundaries(x, y, dx, dy, size, &mut neighbors);
+                self.check_boundaries(x, y, dx, dy, &mut neighbors, visit);
             }
         }
-        neighbors
     }
 }
 
 impl FireSpreadStrategy for VonNeumannNeighborhood {
-    fn spread(&self, x: usize, y: usize, size: usize) -> Vec<(usize, usize)> {
-        let mut neighbors = Vec::new();
+    fn for_each_neighbor(&self, x: usize, y: usize, bounds: Bounds, visit: &mut dyn FnMut(usize, usize)) {
+        let mut neighbors = Distinct::new(bounds);
         for (dx, dy) in &[(-1, 0), (1, 0), (0, -1), (0, 1)] {
-            self.check_boundaries(x, y, *dx, *dy, size, &mut neighbors);
+            self.check_boundaries(x, y, *dx, *dy, &mut neighbors, visit);
+        }
+    }
+}
+
+/// Wind-driven, anisotropic spread over the Moore neighborhood.
+///
+/// The chance to ignite a neighbor is scaled by `exp(strength * cos θ)`, where θ is the
+/// angle between the offset to the neighbor and the wind, so downwind cells catch fire
+/// more easily and upwind cells less. With `reach == 2` the fire also jumps to cells
+/// two steps away within 45° of downwind, at half the weight of the adjacent cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindStrategy {
+    pub direction: f64, // compass degrees the wind blows towards: 0 = north (up), 90 = east
+    pub strength: f64,  // 0.0 is calm, i.e. plain Moore spread
+    pub reach: usize,   // 1 or 2 cells downwind
+}
+
+/// Compass points and their bearings, clockwise from north.
+const COMPASS: [(&str, f64); 8] = [
+    ("N", 0.0), ("NE", 45.0), ("E", 90.0), ("SE", 135.0),
+    ("S", 180.0), ("SW", 225.0), ("W", 270.0), ("NW", 315.0),
+];
+const ARROWS: [&str; 8] = ["↑", "↗", "→", "↘", "↓", "↙", "←", "↖"];
+
+impl Default for WindStrategy {
+    fn default() -> Self {
+        WindStrategy { direction: 90.0, strength: 1.0, reach: 1 }
+    }
+}
+
+impl WindStrategy {
+    /// Parses a compass point (`N`, `NE`, ... `NW`) or a bearing in degrees.
+    pub fn parse_direction(value: &str) -> Option<f64> {
+        let upper = value.trim().to_uppercase();
+        COMPASS
+            .iter()
+            .find(|(name, _)| *name == upper)
+            .map(|&(_, degrees)| degrees)
+            .or_else(|| upper.parse::<f64>().ok().filter(|d| d.is_finite()).map(|d| d.rem_euclid(360.0)))
+    }
+
+    /// Weight of the neighbor at offset `(dx, dy)`; y grows downwards (south).
+    pub fn weight(&self, dx: i32, dy: i32) -> f64 {
+        (self.strength * self.alignment(dx, dy)).exp()
+    }
+
+    /// Nearest compass point and its arrow, e.g. `("E", "→")`.
+    pub fn compass(&self) -> (&'static str, &'static str) {
+        let octant = ((self.direction.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8;
+        (COMPASS[octant].0, ARROWS[octant])
+    }
+
+    /// Cosine of the angle between the offset `(dx, dy)` and the wind.
+    fn alignment(&self, dx: i32, dy: i32) -> f64 {
+        let radians = self.direction.to_radians();
+        let (wx, wy) = (radians.sin(), -radians.cos());
+        (dx as f64 * wx + dy as f64 * wy) / ((dx * dx + dy * dy) as f64).sqrt()
+    }
+}
+
+impl FireSpreadStrategy for WindStrategy {
+    fn for_each_neighbor(&self, x: usize, y: usize, bounds: Bounds, visit: &mut dyn FnMut(usize, usize)) {
+        self.for_each_weighted_neighbor(x, y, bounds, &mut |nx, ny, _| visit(nx, ny));
+    }
+
+    fn for_each_weighted_neighbor(&self, x: usize, y: usize, bounds: Bounds, visit: &mut dyn FnMut(usize, usize, f64)) {
+        let mut neighbors = Distinct::new(bounds);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                if dx == 0 && dy == 0 { continue; }
+                let weight = self.weight(dx, dy);
+                if let Some((nx, ny)) = neighbors.offset(x, y, dx, dy) {
+                    visit(nx, ny, weight);
+                }
+
+                // within 45° of downwind
+                if self.reach >= 2
+                    && self.alignment(dx, dy) >= std::f64::consts::FRAC_1_SQRT_2 - 1e-9
+                    && let Some((nx, ny)) = neighbors.offset(x, y, 2 * dx, 2 * dy)
+                {
+                    visit(nx, ny, weight * 0.5);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for WindStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, arrow) = self.compass();
+        write!(f, "Wind {} {} ({:.0}°), strength {:.2}, reach {}", arrow, name, self.direction, self.strength, self.reach)
+    }
+}
+
+/// Slope-aware spread on top of any other strategy.
+///
+/// The weight the base strategy gives a neighbor is scaled by `exp(factor * θ)`,
+/// where θ is the slope angle from the burning cell up to the neighbor,
+/// so fire runs faster uphill and slower downhill.
+#[derive(Clone, Copy)]
+pub struct SlopeStrategy<'a> {
+    pub base: &'a dyn FireSpreadStrategy,
+    pub terrain: &'a Terrain,
+    pub relief: f64, // height of the highest point above the lowest, in cell widths
+    pub factor: f64, // per radian of slope
+}
+
+impl SlopeStrategy<'_> {
+    /// Weight of spreading from `(x, y)` to `(nx, ny)` due to the slope between them.
+    pub fn weight(&self, x: usize, y: usize, nx: usize, ny: usize, boundary: Boundary) -> f64 {
+        // on a torus the shortest offset, so wrapped neighbors count as adjacent
+        let span = |a: usize, b: usize, len: usize| {
+            let d = a.abs_diff(b);
+            if boundary == Boundary::Periodic { d.min(len - d) as f64 } else { d as f64 }
+        };
+        let distance = span(x, nx, self.terrain.width).hypot(span(y, ny, self.terrain.height));
+        let rise = (self.terrain.get(nx, ny) - self.terrain.get(x, y)) * self.relief;
+        (self.factor * rise.atan2(distance)).exp()
+    }
+}
+
+impl FireSpreadStrategy for SlopeStrategy<'_> {
+    fn for_each_neighbor(&self, x: usize, y: usize, bounds: Bounds, visit: &mut dyn FnMut(usize, usize)) {
+        self.base.for_each_neighbor(x, y, bounds, visit);
+    }
+
+    fn for_each_weighted_neighbor(&self, x: usize, y: usize, bounds: Bounds, visit: &mut dyn FnMut(usize, usize, f64)) {
+        self.base.for_each_weighted_neighbor(x, y, bounds, &mut |nx, ny, weight| {
+            visit(nx, ny, weight * self.weight(x, y, nx, ny, bounds.boundary));
+        });
+    }
+}
+
+/// Adapter for strategies still returning a freshly allocated `Vec` of neighbors,
+/// e.g. `VecSpread(|x, y, bounds: Bounds| my_old_spread(x, y, bounds.width))`.
+/// Out-of-grid coordinates it returns are dropped.
+#[derive(Debug, Clone, Copy)]
+pub struct VecSpread<F>(pub F);
+
+impl<F> FireSpreadStrategy for VecSpread<F>
+where
+    F: Fn(usize, usize, Bounds) -> Vec<(usize, usize)>,
+{
+    fn for_each_neighbor(&self, x: usize, y: usize, bounds: Bounds, visit: &mut dyn FnMut(usize, usize)) {
+        for (nx, ny) in (self.0)(x, y, bounds) {
+            if nx < bounds.width && ny < bounds.height {
+                visit(nx, ny);
+            }
         }
-        neighbors
     }
 }
 
@@ -39,8 +304,8 @@ trait NeighborhoodHelper {
         y: usize,
         dx: i32,
         dy: i32,
-        size: usize,
-        neighbors: &mut Vec<(usize, usize)>
+        neighbors: &mut Distinct,
+        visit: &mut dyn FnMut(usize, usize)
     );
 }
 
@@ -51,14 +316,11 @@ impl<T> NeighborhoodHelper for T where T: FireSpreadStrategy {
         y: usize,
         dx: i32,
         dy: i32,
-        size: usize,
-        neighbors: &mut Vec<(usize, usize)>
+        neighbors: &mut Distinct,
+        visit: &mut dyn FnMut(usize, usize)
     ) {
-        let nx = x as i32 + dx;
-        let ny = y as i32 + dy;
-
-        if nx >= 0 && ny >= 0 && nx < size as i32 && ny < size as i32 {
-            neighbors.push((nx as usize, ny as usize));
+        if let Some((nx, ny)) = neighbors.offset(x, y, dx, dy) {
+            visit(nx, ny);
         }
     }
 }
@@ -71,7 +333,7 @@ mod tests {
     #[test]
     fn test_moore_neighborhood_center() {
         let moore = MooreNeighborhood;
-        let neighbors = moore.spread(1, 1, 3);
+        let neighbors = moore.spread(1, 1, Bounds::new(3, 3));
         let expected = vec![
             (0, 0), (0, 1), (0, 2),
             (1, 0),         (1, 2),
@@ -84,7 +346,7 @@ mod tests {
     #[test]
     fn test_moore_neighborhood_edge() {
         let moore = MooreNeighborhood;
-        let neighbors = moore.spread(0, 1, 3);
+        let neighbors = moore.spread(0, 1, Bounds::new(3, 3));
         let expected = vec![
             (0, 0), (0, 2),
             (1, 0), (1, 1), (1, 2)
@@ -96,7 +358,7 @@ mod tests {
     #[test]
     fn test_moore_neighborhood_corner() {
         let moore = MooreNeighborhood;
-        let neighbors = moore.spread(0, 0, 3);
+        let neighbors = moore.spread(0, 0, Bounds::new(3, 3));
         let expected = vec![
             (0, 1),
             (1, 0), (1, 1)
@@ -108,7 +370,7 @@ mod tests {
     #[test]
     fn test_von_neumann_center() {
         let vn = VonNeumannNeighborhood;
-        let neighbors = vn.spread(1, 1, 3);
+        let neighbors = vn.spread(1, 1, Bounds::new(3, 3));
         let expected = vec![
             (0, 1),
             (1, 0), (1, 2),
@@ -121,7 +383,7 @@ mod tests {
     #[test]
     fn test_von_neumann_edge() {
         let vn = VonNeumannNeighborhood;
-        let neighbors = vn.spread(0, 1, 3);
+        let neighbors = vn.spread(0, 1, Bounds::new(3, 3));
         let expected = vec![
             (1, 1), // From (-1, 0) is invalid
             (0, 0),
@@ -134,7 +396,7 @@ mod tests {
     #[test]
     fn test_von_neumann_corner() {
         let vn = VonNeumannNeighborhood;
-        let neighbors = vn.spread(0, 0, 3);
+        let neighbors = vn.spread(0, 0, Bounds::new(3, 3));
         let expected = vec![
             (1, 0),  // From (0, -1) is invalid
             (0, 1)   // From (-1, 0) is invalid
@@ -147,10 +409,112 @@ mod tests {
     fn test_boundary_check() {
         let moore = MooreNeighborhood;
         let mut neighbors = Vec::new();
-        moore.check_boundaries(2, 2, 1, 1, 3, &mut neighbors);
+        moore.check_boundaries(2, 2, 1, 1, &mut Distinct::new(Bounds::new(3, 3)), &mut |nx, ny| neighbors.push((nx, ny)));
         assert_eq!(neighbors, vec![]);  // (3,3) is out of bounds
     }
 
+    #[test]
+    fn test_periodic_wraps_around() {
+        let bounds = Bounds::new(4, 3).with_boundary(Boundary::Periodic);
+        let neighbors = VonNeumannNeighborhood.spread(0, 0, bounds);
+        assert_eq!(sorted(neighbors), vec![(0, 1), (0, 2), (1, 0), (3, 0)]);
+
+        // every cell has a full Moore neighborhood on a torus
+        assert_eq!(MooreNeighborhood.spread(3, 2, bounds).len(), 8);
+
+        // two wide: both horizontal offsets wrap onto the same cell
+        let narrow = Bounds::new(2, 5).with_boundary(Boundary::Periodic);
+        assert_eq!(sorted(VonNeumannNeighborhood.spread(0, 2, narrow)), vec![(0, 1), (0, 3), (1, 2)]);
+        assert_eq!(MooreNeighborhood.spread(0, 2, narrow).len(), 5);
+        let wind = WindStrategy { direction: 90.0, strength: 1.0, reach: 2 };
+        let mut cells = sorted(wind.spread(0, 2, narrow));
+        let count = cells.len();
+        cells.dedup();
+        assert_eq!(cells.len(), count);
+    }
+
+    #[test]
+    fn test_wind_favors_downwind() {
+        let wind = WindStrategy { direction: 90.0, strength: 1.0, reach: 1 }; // blowing east
+        assert!(wind.weight(1, 0) > 1.0);
+        assert!(wind.weight(-1, 0) < 1.0);
+        assert!((wind.weight(0, 1) - 1.0).abs() < 1e-9);
+        assert!(wind.weight(1, -1) > wind.weight(0, -1));
+
+        let calm = WindStrategy { strength: 0.0, ..wind };
+        assert_eq!(sorted(calm.spread(1, 1, Bounds::new(3, 3))), sorted(MooreNeighborhood.spread(1, 1, Bounds::new(3, 3))));
+    }
+
+    #[test]
+    fn test_wind_reaches_two_cells_downwind() {
+        let wind = WindStrategy { direction: 0.0, strength: 2.0, reach: 2 }; // blowing north (up)
+        let neighbors = wind.spread(2, 2, Bounds::new(5, 5));
+        assert_eq!(neighbors.len(), 8 + 3);
+        for cell in [(0, 0), (2, 0), (4, 0)] {
+            assert!(neighbors.contains(&cell));
+        }
+        assert!(!neighbors.contains(&(2, 4)));
+    }
+
+    #[test]
+    fn test_wind_direction_names() {
+        assert_eq!(WindStrategy::parse_direction("ne"), Some(45.0));
+        assert_eq!(WindStrategy::parse_direction("-90"), Some(270.0));
+        assert_eq!(WindStrategy::parse_direction("up"), None);
+
+        let wind = WindStrategy { direction: 200.0, ..WindStrategy::default() };
+        assert_eq!(wind.compass(), ("S", "↓"));
+    }
+
+    #[test]
+    fn test_slope_favors_uphill() {
+        // a ramp rising to the east
+        let terrain = Terrain::from_heights(3, 1, vec![0.0, 1.0, 2.0]).unwrap();
+        let slope = SlopeStrategy { base: &MooreNeighborhood, terrain: &terrain, relief: 2.0, factor: 1.0 };
+
+        assert!((slope.weight(1, 0, 2, 0, Boundary::Open) - std::f64::consts::FRAC_PI_4.exp()).abs() < 1e-9);
+        assert!((slope.weight(1, 0, 0, 0, Boundary::Open) - (-std::f64::consts::FRAC_PI_4).exp()).abs() < 1e-9);
+        // a jump two cells east is one cell west on a torus only
+        assert!((slope.weight(0, 0, 2, 0, Boundary::Open) - std::f64::consts::FRAC_PI_4.exp()).abs() < 1e-9);
+        assert!((slope.weight(0, 0, 2, 0, Boundary::Periodic) - 2f64.atan().exp()).abs() < 1e-9);
+
+        let mut weights = Vec::new();
+        slope.for_each_weighted_neighbor(1, 0, Bounds::new(3, 1), &mut |nx, _, w| weights.push((nx, w)));
+        assert_eq!(weights.len(), 2);
+        assert!(weights.iter().find(|(nx, _)| *nx == 2).unwrap().1 > 1.0);
+        assert!(weights.iter().find(|(nx, _)| *nx == 0).unwrap().1 < 1.0);
+    }
+
+    #[test]
+    fn test_callback_matches_spread() {
+        let bounds = Bounds::new(5, 4);
+        for (x, y) in [(0, 0), (2, 1), (4, 3)] {
+            let mut visited = Vec::new();
+            MooreNeighborhood.for_each_neighbor(x, y, bounds, &mut |nx, ny| visited.push((nx, ny)));
+            assert_eq!(visited, MooreNeighborhood.spread(x, y, bounds));
+        }
+    }
+
+    #[test]
+    fn test_vec_spread_adapter() {
+        // an old-style strategy: only the cell to the right, without its own bounds check
+        let right = VecSpread(|x: usize, y: usize, _: Bounds| vec![(x + 1, y)]);
+        assert_eq!(right.spread(1, 1, Bounds::new(3, 3)), vec![(2, 1)]);
+        assert_eq!(right.spread(2, 1, Bounds::new(3, 3)), vec![]);
+    }
+
+    #[test]
+    fn test_rectangular_bounds() {
+        // 4 wide, 2 high: x runs along the width, y along the height
+        let moore = MooreNeighborhood;
+        let neighbors = moore.spread(3, 1, Bounds::new(4, 2));
+        assert_eq!(sorted(neighbors), vec![(2, 0), (2, 1), (3, 0)]);
+
+        let vn = VonNeumannNeighborhood;
+        let neighbors = vn.spread(1, 0, Bounds::new(4, 2));
+        assert_eq!(sorted(neighbors), vec![(0, 0), (1, 1), (2, 0)]);
+    }
+
     // Helper to sort coordinates for comparison
     fn sorted(mut vec: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
         vec.sort();
@@ -1,11 +1,18 @@
-use std::collections::VecDeque;
 use std::fmt;
+use std::sync::Arc;
 use rand::Rng;
 use rand::seq::SliceRandom;
-use crate::fire_spread::FireSpreadStrategy;
+use crate::fire_spread::{Boundary, Bounds, FireSpreadStrategy};
+use crate::map::ForestMap;
+use crate::placement::Placement;
+use crate::terrain::Terrain;
+use crate::vegetation::{Vegetation, VegetationTable};
+
+/// Arrival time of a cell that has not caught fire.
+const NEVER: u32 = u32::MAX;
 
 // Cell states
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum CellState {
     Empty,
     Tree,
@@ -13,120 +20,540 @@ pub enum CellState {
     Burned,
 }
 
+/// How long a tree burns and when during the burn it can ignite its neighbors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fuel {
+    pub burn_steps: u16,   // timesteps a cell stays Burning
+    pub spread_from: u16,  // first step of the burn (0-based) that can ignite neighbors
+    pub spread_until: u16, // the burn stops igniting neighbors from this step on
+}
+
+impl Default for Fuel {
+    /// Burns out in one step, spreading as it does.
+    fn default() -> Self {
+        Fuel { burn_steps: 1, spread_from: 0, spread_until: u16::MAX }
+    }
+}
+
+impl Fuel {
+    /// Whether a cell `timer` steps into its burn can ignite its neighbors.
+    #[inline]
+    pub fn spreads_at(&self, timer: u16) -> bool {
+        (self.spread_from..self.spread_until).contains(&timer)
+    }
+}
+
 pub struct Forest {
-    pub size: usize,
-    pub grid: Vec<Vec<CellState>>,
-    fire_front: VecDeque<(usize, usize)>,
+    pub width: usize,
+    pub height: usize,
+    pub boundary: Boundary,
+    pub spread_prob: f64, // chance that a burning cell ignites each tree neighbor
+    terrain: Option<Arc<Terrain>>,
+    pub classes: VegetationTable, // flammability of every vegetation class
+    cells: Vec<CellState>, // row-major: index = y * width + x
+    vegetation: Vec<Vegetation>,
+    fuel_left: Vec<u16>,   // steps each cell can still burn
+    burn_timer: Vec<u16>,  // steps each cell has been burning
+    age: Vec<u16>,         // years since a tree grew, or since a burned cell's fire
+    arrival: Vec<u32>,     // step each cell last caught fire, NEVER if it has not
+    clock: u32,            // timesteps of fire spread so far
+    fire_front: Vec<(usize, usize)>,
+    next_front: Vec<(usize, usize)>, // spare buffer, swapped with fire_front every step
     pub burned_count: usize,
     pub total_trees: usize,
+    class_trees: [usize; Vegetation::COUNT],
+    class_burned: [usize; Vegetation::COUNT],
 }
 
 impl Forest {
-    pub fn new(size: usize, density: f64) -> Self {
-        let total_cells = size * size;
-        // compute exact number of trees (round to nearest)
-        let target_trees = (density * total_cells as f64).round() as usize;
+    /// A forest of conifers covering `density` of the grid.
+    pub fn new<R: Rng + ?Sized>(width: usize, height: usize, density: f64, rng: &mut R) -> Self {
+        Self::generate(width, height, &[(Vegetation::Conifer, density)], rng)
+    }
+
+    /// A forest with each vegetation class covering its own fraction of the grid.
+    /// Fuel classes, then water and rock, are placed in order on shuffled cells; the
+    /// rest is bare ground.
+    pub fn generate<R: Rng + ?Sized>(
+        width: usize,
+        height: usize,
+        densities: &[(Vegetation, f64)],
+        rng: &mut R,
+    ) -> Self {
+        Self::generate_with(width, height, densities, Placement::Uniform, rng)
+    }
+
+    /// Like [`Forest::generate`], with the trees laid out by `placement`. Under a clustered
+    /// placement the fuel classes share the patches at random and water and rock
+    /// fill the cells ranked last, away from the trees.
+    pub fn generate_with<R: Rng + ?Sized>(
+        width: usize,
+        height: usize,
+        densities: &[(Vegetation, f64)],
+        placement: Placement,
+        rng: &mut R,
+    ) -> Self {
+        let total_cells = width * height;
 
         // start with an all-empty grid
-        let mut grid = vec![vec![CellState::Empty; size]; size];
+        let mut cells = vec![CellState::Empty; total_cells];
+        let mut vegetation = vec![Vegetation::default(); total_cells];
+        let mut class_trees = [0; Vegetation::COUNT];
+
+        // fuel classes first, as the placement ranks the cells meant for trees first
+        let mut densities = densities.to_vec();
+        densities.sort_by_key(|(class, _)| !class.is_fuel());
 
-        let mut indices: Vec<(usize, usize)> = (0..size)
-            .flat_map(|x| (0..size).map(move |y| (x, y)))
+        // compute exact number of cells (round to nearest)
+        let mut placed = 0;
+        let counts: Vec<usize> = densities
+            .iter()
+            .map(|&(_, density)| {
+                let count = ((density * total_cells as f64).round() as usize).min(total_cells - placed);
+                placed += count;
+                count
+            })
             .collect();
+        let fuel: usize = densities.iter().zip(&counts).filter(|((class, _), _)| class.is_fuel()).map(|(_, &n)| n).sum();
 
-        let mut rng = rand::rng();
-        indices.shuffle(&mut rng);
+        let mut indices = placement.order(width, height, fuel, rng);
+        if placement != Placement::Uniform {
+            indices[..fuel].shuffle(rng);
+            indices[fuel..].reverse();
+        }
 
-        for &(x, y) in indices.iter().take(target_trees) {
-            grid[x][y] = CellState::Tree;
+        let mut placed = 0;
+        for (&(class, _), &count) in densities.iter().zip(&counts) {
+            for &index in &indices[placed..placed + count] {
+                vegetation[index] = class;
+                if class.is_fuel() {
+                    cells[index] = CellState::Tree;
+                }
+            }
+            if class.is_fuel() {
+                class_trees[class as usize] += count;
+            }
+            placed += count;
         }
 
+        Self::assemble(width, height, cells, vegetation, class_trees)
+    }
+
+    /// A fresh forest over the given cells, fueled by the default classes.
+    fn assemble(
+        width: usize,
+        height: usize,
+        cells: Vec<CellState>,
+        vegetation: Vec<Vegetation>,
+        class_trees: [usize; Vegetation::COUNT],
+    ) -> Self {
+        let total_cells = width * height;
         // track the count:
-        let total_trees = target_trees;
+        let total_trees = class_trees.iter().sum();
 
-        Forest {
-            size,
-            grid,
-            fire_front: VecDeque::new(),
+        let mut forest = Forest {
+            width,
+            height,
+            boundary: Boundary::Open,
+            spread_prob: 1.0,
+            terrain: None,
+            classes: VegetationTable::default(),
+            fuel_left: vec![0; total_cells],
+            burn_timer: vec![0; total_cells],
+            age: vec![0; total_cells],
+            arrival: vec![NEVER; total_cells],
+            clock: 0,
+            cells,
+            vegetation,
+            fire_front: Vec::new(),
+            next_front: Vec::new(),
             burned_count: 0,
             total_trees,
+            class_trees,
+            class_burned: [0; Vegetation::COUNT],
+        };
+        forest.refuel();
+        forest
+    }
+
+    /// A forest laid out by a map. Burning cells of the map form the initial fire front
+    /// and count as trees, burned by the coming fire once they burn out; burned cells
+    /// stay burned and count as neither.
+    pub fn from_map(map: &ForestMap) -> Self {
+        let (cells, vegetation): (Vec<_>, Vec<_>) =
+            (0..map.height).flat_map(|y| (0..map.width).map(move |x| map.get(x, y))).unzip();
+        let mut class_trees = [0; Vegetation::COUNT];
+        for (&state, &class) in cells.iter().zip(&vegetation) {
+            if matches!(state, CellState::Tree | CellState::Burning) {
+                class_trees[class as usize] += 1;
+            }
+        }
+
+        let mut forest = Self::assemble(map.width, map.height, cells, vegetation, class_trees);
+        forest.fire_front = (0..forest.cells.len())
+            .filter(|&index| forest.cells[index] == CellState::Burning)
+            .map(|index| forest.coords(index))
+            .collect();
+        for &(x, y) in &forest.fire_front {
+            let index = y * forest.width + x;
+            forest.arrival[index] = 0;
+        }
+        forest
+    }
+
+    /// Reads a forest from an ASCII, CSV, PGM or PNG map; see [`ForestMap::load`].
+    pub fn from_file(path: &std::path::Path) -> Result<Self, String> {
+        ForestMap::load(path).map(|map| Self::from_map(&map))
+    }
+
+    /// Sets the edge behavior used when the fire spreads.
+    pub fn with_boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Sets the per-neighbor ignition probability; 1.0 spreads with certainty.
+    pub fn with_spread_prob(mut self, spread_prob: f64) -> Self {
+        self.spread_prob = spread_prob;
+        self
+    }
+
+    /// Sets the flammability of every vegetation class.
+    pub fn with_classes(mut self, classes: VegetationTable) -> Self {
+        self.classes = classes;
+        self.refuel();
+        self
+    }
+
+    /// Gives every fuel class the same burn duration and spread phase.
+    pub fn with_fuel(mut self, fuel: Fuel) -> Self {
+        for class in Vegetation::ALL.into_iter().filter(|v| v.is_fuel()) {
+            self.classes[class].fuel = fuel;
+        }
+        self.refuel();
+        self
+    }
+
+    fn refuel(&mut self) {
+        for (fuel, &class) in self.fuel_left.iter_mut().zip(&self.vegetation) {
+            *fuel = self.classes[class].fuel.burn_steps.max(1);
+        }
+    }
+
+    /// Lays the forest over an elevation layer of the same size.
+    pub fn with_terrain(mut self, terrain: Option<Arc<Terrain>>) -> Self {
+        debug_assert!(terrain.as_ref().is_none_or(|t| t.width == self.width && t.height == self.height));
+        self.terrain = terrain;
+        self
+    }
+
+    pub fn terrain(&self) -> Option<&Terrain> {
+        self.terrain.as_deref()
+    }
+
+    /// Index of `(x, y)` in the row-major cell buffer.
+    #[inline]
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    /// Coordinates of a cell index, inverse of [`Forest::index`].
+    #[inline]
+    pub fn coords(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> CellState {
+        self.cells[self.index(x, y)]
+    }
+
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize, state: CellState) {
+        let index = self.index(x, y);
+        self.cells[index] = state;
+    }
+
+    #[inline]
+    pub fn vegetation(&self, x: usize, y: usize) -> Vegetation {
+        self.vegetation[self.index(x, y)]
+    }
+
+    /// Glyph of the cell at `index`: its vegetation while unburnt, then the fire and the scar.
+    pub fn glyph(&self, index: usize) -> &'static str {
+        match self.cells[index] {
+            CellState::Empty if self.vegetation[index].is_fuel() => "  ",
+            CellState::Empty | CellState::Tree => self.vegetation[index].glyph(),
+            CellState::Burning => "🔥",
+            CellState::Burned => "◼️", // 🪨
+            // CellState::Lightning => "⚡️",
         }
     }
 
-    pub fn ignite(&mut self, pos: Option<(usize, usize)>) -> bool {
-        let (x, y) = pos.unwrap_or_else(|| self.random_strike());
-        if self.grid[x][y] == CellState::Tree {
-            self.grid[x][y] = CellState::Burning;
-            self.fire_front.push_back((x, y));
+    /// Trees of each vegetation class at the start, indexed by `Vegetation as usize`.
+    pub fn class_trees(&self) -> [usize; Vegetation::COUNT] {
+        self.class_trees
+    }
+
+    /// Burned trees of each vegetation class, indexed by `Vegetation as usize`.
+    pub fn class_burned(&self) -> [usize; Vegetation::COUNT] {
+        self.class_burned
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds::new(self.width, self.height).with_boundary(self.boundary)
+    }
+
+    /// All cells in row-major order.
+    pub fn cells(&self) -> &[CellState] {
+        &self.cells
+    }
+
+    /// Rows from top (`y = 0`) to bottom, each `width` cells long.
+    pub fn rows(&self) -> impl Iterator<Item = &[CellState]> {
+        self.cells.chunks_exact(self.width)
+    }
+
+    /// Hands every in-grid neighbor of `(x, y)` under `strategy` to `visit`, with its
+    /// current state, without allocating.
+    pub fn for_each_neighbor(
+        &self,
+        x: usize,
+        y: usize,
+        strategy: &dyn FireSpreadStrategy,
+        visit: &mut dyn FnMut((usize, usize), CellState),
+    ) {
+        strategy.for_each_neighbor(x, y, self.bounds(), &mut |nx, ny| visit((nx, ny), self.get(nx, ny)));
+    }
+
+    /// The cells burning right now.
+    pub fn fire_front(&self) -> &[(usize, usize)] {
+        &self.fire_front
+    }
+
+    /// Number of cells burning right now.
+    pub fn burning_count(&self) -> usize {
+        self.fire_front.len()
+    }
+
+    pub fn ignite<R: Rng + ?Sized>(&mut self, pos: Option<(usize, usize)>, rng: &mut R) -> bool {
+        let (x, y) = pos.unwrap_or_else(|| self.random_strike(rng));
+        if self.get(x, y) == CellState::Tree {
+            self.set(x, y, CellState::Burning);
+            let index = self.index(x, y);
+            self.arrival[index] = self.clock;
+            self.fire_front.push((x, y));
         }
         !self.fire_front.is_empty()
     }
 
-    pub fn pick_random_tree(&self) -> Option<(usize, usize)> {
-        let mut rng = rand::rng();
-        let mut selected: Option<(usize, usize)> = None;
+    pub fn pick_random_tree<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<(usize, usize)> {
+        let mut selected: Option<usize> = None;
         let mut count = 0;
 
-        for (x, row) in self.grid.iter().enumerate() {
-            for (y, cell) in row.iter().enumerate() {
-                if *cell == CellState::Tree {
-                    count += 1;
-                    // Replace with probability 1/count
-                    if rng.random_range(0..count) == 0 {
-                        selected = Some((x, y));
-                    }
+        for (index, cell) in self.cells.iter().enumerate() {
+            if *cell == CellState::Tree {
+                count += 1;
+                // Replace with probability 1/count
+                if rng.random_range(0..count) == 0 {
+                    selected = Some(index);
                 }
             }
         }
 
-        selected
+        selected.map(|index| self.coords(index))
     }
 
 
-    pub fn fire_spread(&mut self, strategy: &dyn FireSpreadStrategy) -> bool {
+    /// Advances the fire by one timestep. Each burning cell in the spread phase of its
+    /// fuel ignites every tree neighbor independently with probability `spread_prob`,
+    /// scaled by the weight the strategy gives that neighbor and by the ignition of the
+    /// neighbor's class, then uses up one step of fuel and burns out when none is left.
+    /// Returns true once the fire is out.
+    pub fn fire_spread<R: Rng + ?Sized>(&mut self, strategy: &dyn FireSpreadStrategy, rng: &mut R) -> bool {
         if self.fire_front.is_empty() {
             return false;
         }
 
-        let current_burning = self.fire_front.drain(..).collect::<Vec<_>>();
+        // the cells burning this step; new ignitions go to the (empty) spare buffer
+        let current_burning = std::mem::replace(&mut self.fire_front, std::mem::take(&mut self.next_front));
+        let bounds = self.bounds();
+        let spread_prob = self.spread_prob;
+        self.clock += 1;
+        let now = self.clock;
 
-        for (x, y) in current_burning {
-            self.burned_count += 1;
-            self.grid[x][y] = CellState::Burned;
+        for &(x, y) in &current_burning {
+            let cell = self.index(x, y);
+            let class = self.vegetation[cell];
 
-            for (nx, ny) in strategy.spread(x, y, self.size) {
-                if nx < self.size && ny < self.size && self.grid[nx][ny] == CellState::Tree {
-                    self.grid[nx][ny] = CellState::Burning;
-                    self.fire_front.push_back((nx, ny));
-                }
+            if self.classes[class].fuel.spreads_at(self.burn_timer[cell]) {
+                strategy.for_each_weighted_neighbor(x, y, bounds, &mut |nx, ny, weight| {
+                    // a strategy that skips `Bounds` would otherwise ignite a cell on another row
+                    if nx >= bounds.width || ny >= bounds.height {
+                        return;
+                    }
+                    let index = ny * self.width + nx;
+                    if self.cells[index] != CellState::Tree {
+                        return;
+                    }
+                    // certain ignitions skip the random draw
+                    let chance = spread_prob * weight * self.classes[self.vegetation[index]].ignition;
+                    if chance >= 1.0 || rng.random_bool(chance.max(0.0)) {
+                        self.cells[index] = CellState::Burning;
+                        self.arrival[index] = now;
+                        self.fire_front.push((nx, ny));
+                    }
+                });
+            }
+
+            self.burn_timer[cell] += 1;
+            self.fuel_left[cell] -= 1;
+            if self.fuel_left[cell] == 0 {
+                self.age[cell] = 0;
+                self.burned_count += 1;
+                self.class_burned[class as usize] += 1;
+                self.cells[cell] = CellState::Burned;
+            } else {
+                self.fire_front.push((x, y));
             }
         }
 
+        self.next_front = current_burning;
+        self.next_front.clear();
+
         self.fire_front.is_empty()
     }
 
-    fn random_strike(&self) -> (usize, usize) {
-        let mut rng = rand::rng();
-        let (x, y) = (rng.random_range(0..self.size), rng.random_range(0..self.size));
+    /// Clears burned cells back to empty ground, ready to regrow.
+    pub fn clear_burned(&mut self) {
+        for index in 0..self.cells.len() {
+            if self.cells[index] == CellState::Burned {
+                self.cells[index] = CellState::Empty;
+                self.fuel_left[index] = self.classes[self.vegetation[index]].fuel.burn_steps.max(1);
+                self.burn_timer[index] = 0;
+            }
+        }
+    }
+
+    /// Grows a tree on every empty fuel cell with probability `growth`.
+    /// Returns the number of new trees.
+    pub fn regrow<R: Rng + ?Sized>(&mut self, growth: f64, rng: &mut R) -> usize {
+        let mut grown = 0;
+        sample_cells(self.cells.len(), growth, rng, |index| {
+            if self.cells[index] == CellState::Empty && self.vegetation[index].is_fuel() {
+                self.cells[index] = CellState::Tree;
+                self.age[index] = 0;
+                self.arrival[index] = NEVER;
+                grown += 1;
+            }
+        });
+        grown
+    }
+
+    /// Grows a tree back on burned cells, each with the probability `chance` gives
+    /// for the years since its fire. Returns the number of new trees.
+    pub fn regrow_burned<R: Rng + ?Sized>(&mut self, rng: &mut R, chance: impl Fn(u16) -> f64) -> usize {
+        let mut grown = 0;
+        for index in 0..self.cells.len() {
+            if self.cells[index] == CellState::Burned && rng.random_bool(chance(self.age[index]).clamp(0.0, 1.0)) {
+                self.cells[index] = CellState::Tree;
+                self.fuel_left[index] = self.classes[self.vegetation[index]].fuel.burn_steps.max(1);
+                self.burn_timer[index] = 0;
+                self.age[index] = 0;
+                self.arrival[index] = NEVER;
+                grown += 1;
+            }
+        }
+        grown
+    }
+
+    /// Ages every tree and every burned cell by one year.
+    pub fn age_one_year(&mut self) {
+        for (age, cell) in self.age.iter_mut().zip(&self.cells) {
+            if matches!(cell, CellState::Tree | CellState::Burned) {
+                *age = age.saturating_add(1);
+            }
+        }
+    }
+
+    /// Years since a tree grew at `(x, y)`, or since the fire of a burned cell.
+    #[inline]
+    pub fn age(&self, x: usize, y: usize) -> u16 {
+        self.age[self.index(x, y)]
+    }
+
+    /// Timestep at which the cell last caught fire: 0 for the strike (or the burning
+    /// cells of a map), `k` for cells lit by the `k`-th call to [`Forest::fire_spread`].
+    pub fn arrival(&self, x: usize, y: usize) -> Option<u32> {
+        Some(self.arrival[self.index(x, y)]).filter(|&step| step != NEVER)
+    }
+
+    /// Arrival times of all cells in row-major order, as [`Forest::arrival`].
+    pub fn arrival_times(&self) -> impl Iterator<Item = Option<u32>> + Clone + '_ {
+        self.arrival.iter().map(|&step| Some(step).filter(|&step| step != NEVER))
+    }
+
+    /// Timesteps of fire spread run on this forest so far.
+    pub fn clock(&self) -> u32 {
+        self.clock
+    }
+
+    /// Number of cells holding an unburnt tree right now.
+    pub fn tree_count(&self) -> usize {
+        self.cells.iter().filter(|&&cell| cell == CellState::Tree).count()
+    }
+
+    /// Cells struck by lightning, each cell independently with probability `lightning`.
+    /// Strikes on cells without a tree do nothing.
+    pub fn lightning_strikes<R: Rng + ?Sized>(&self, lightning: f64, rng: &mut R) -> Vec<(usize, usize)> {
+        let mut strikes = Vec::new();
+        sample_cells(self.cells.len(), lightning, rng, |index| {
+            if self.cells[index] == CellState::Tree {
+                strikes.push(self.coords(index));
+            }
+        });
+        strikes
+    }
+
+    fn random_strike<R: Rng + ?Sized>(&self, rng: &mut R) -> (usize, usize) {
+        let (x, y) = (rng.random_range(0..self.width), rng.random_range(0..self.height));
         (x, y)
     }
 
     pub fn density(&self) -> f64 {
-         self.total_trees as f64 / (self.size * self.size) as f64
+         self.total_trees as f64 / (self.width * self.height) as f64
+    }
+}
+
+/// Calls `visit` with every index below `len` chosen independently with probability `q`,
+/// jumping geometric gaps instead of drawing once per cell.
+fn sample_cells<R: Rng + ?Sized>(len: usize, q: f64, rng: &mut R, mut visit: impl FnMut(usize)) {
+    if q <= 0.0 {
+        return;
+    }
+    if q >= 1.0 {
+        (0..len).for_each(visit);
+        return;
+    }
+    let log_miss = (1.0 - q).ln();
+    let mut index = 0usize;
+    loop {
+        let u: f64 = rng.random();
+        let gap = ((1.0 - u).ln() / log_miss).floor() as usize; // saturates on huge gaps
+        index = index.saturating_add(gap);
+        if index >= len {
+            break;
+        }
+        visit(index);
+        index += 1;
     }
 }
 
 impl fmt::Display for Forest {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for row in &self.grid {
-            for cell in row {
-                write!(f, "{}", match cell {
-                    CellState::Empty => "  ", // 🟩❇️🌾🌿🌻
-                    CellState::Tree => "🌲", // 🌳🌴
-                    CellState::Burning => "🔥",
-                    CellState::Burned => "◼️", // 🪨
-                    // CellState::Lightning => "⚡️",
-                })?;
+        for row in 0..self.height {
+            for index in row * self.width..(row + 1) * self.width {
+                write!(f, "{}", self.glyph(index))?;
             }
             writeln!(f)?;
         }
@@ -139,11 +566,345 @@ impl fmt::Display for Forest {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::simulation::rng_from_seed;
 
     #[test]
     fn test_tree_count() {
-        let forest:Forest = Forest::new(100, 0.5);
+        let mut rng = rand::rng();
+        let forest:Forest = Forest::new(100, 100, 0.5, &mut rng);
         let tree_count = forest.total_trees;
         assert!(tree_count > 4000 && tree_count < 6000);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_same_seed_same_forest() {
+        let a = Forest::new(30, 30, 0.5, &mut rng_from_seed(7));
+        let b = Forest::new(30, 30, 0.5, &mut rng_from_seed(7));
+        assert!(a.cells() == b.cells());
+
+        let c = Forest::new(30, 30, 0.5, &mut rng_from_seed(8));
+        assert!(a.cells() != c.cells());
+    }
+
+    #[test]
+    fn test_rectangular_strip_burns_along() {
+        // a full strip 50 wide and 3 high burns out from any strike
+        let mut rng = rng_from_seed(1);
+        let mut forest = Forest::new(50, 3, 1.0, &mut rng);
+        assert_eq!(forest.rows().count(), 3);
+        assert!(forest.rows().all(|row| row.len() == 50));
+
+        forest.ignite(Some((49, 2)), &mut rng);
+        while !forest.fire_spread(&crate::fire_spread::VonNeumannNeighborhood, &mut rng) {}
+        assert_eq!(forest.burned_count, 150);
+        assert_eq!(forest.density(), 1.0);
+    }
+
+    #[test]
+    fn test_periodic_fire_crosses_edge() {
+        // a single row of trees at the top and bottom edges, touching only across the wrap
+        let mut rng = rng_from_seed(2);
+        let mut forest = Forest::new(5, 4, 0.0, &mut rng).with_boundary(Boundary::Periodic);
+        for x in 0..5 {
+            forest.set(x, 0, CellState::Tree);
+            forest.set(x, 3, CellState::Tree);
+        }
+
+        forest.ignite(Some((2, 0)), &mut rng);
+        while !forest.fire_spread(&crate::fire_spread::VonNeumannNeighborhood, &mut rng) {}
+        assert_eq!(forest.burned_count, 10);
+
+        // the same layout with open edges only burns the top row
+        let mut open = Forest::new(5, 4, 0.0, &mut rng);
+        for x in 0..5 {
+            open.set(x, 0, CellState::Tree);
+            open.set(x, 3, CellState::Tree);
+        }
+        open.ignite(Some((2, 0)), &mut rng);
+        while !open.fire_spread(&crate::fire_spread::VonNeumannNeighborhood, &mut rng) {}
+        assert_eq!(open.burned_count, 5);
+    }
+
+    #[test]
+    fn test_neighbors_off_the_grid_are_dropped() {
+        // spreads east without consulting the bounds
+        struct East;
+        impl FireSpreadStrategy for East {
+            fn for_each_neighbor(&self, x: usize, y: usize, _: Bounds, visit: &mut dyn FnMut(usize, usize)) {
+                visit(x + 1, y);
+            }
+        }
+
+        let mut rng = rng_from_seed(0);
+        let mut forest = Forest::new(3, 2, 1.0, &mut rng);
+        forest.ignite(Some((2, 0)), &mut rng);
+        while !forest.fire_spread(&East, &mut rng) {}
+        // (3, 0) would be (0, 1) in the flat buffer
+        assert_eq!(forest.burned_count, 1);
+    }
+
+    #[test]
+    fn test_spread_probability() {
+        let burn = |spread_prob: f64, seed: u64| {
+            let mut rng = rng_from_seed(seed);
+            let mut forest = Forest::new(60, 60, 1.0, &mut rng).with_spread_prob(spread_prob);
+            forest.ignite(Some((30, 30)), &mut rng);
+            while !forest.fire_spread(&crate::fire_spread::MooreNeighborhood, &mut rng) {}
+            forest.burned_count
+        };
+
+        assert_eq!(burn(1.0, 0), 3600);
+        assert_eq!(burn(0.0, 0), 1);
+        // far below the bond threshold the fire dies out near the strike
+        assert!(burn(0.05, 0) < 100);
+        assert_eq!(burn(0.5, 9), burn(0.5, 9));
+    }
+
+    #[test]
+    fn test_wind_elongates_burn_downwind() {
+        let wind = crate::fire_spread::WindStrategy { direction: 90.0, strength: 2.0, reach: 1 }; // east
+
+        // burn from the middle and compare how far the scar reaches east and west
+        let (mut east, mut west) = (0, 0);
+        for seed in 0..5 {
+            let mut rng = rng_from_seed(seed);
+            let mut forest = Forest::new(40, 40, 1.0, &mut rng).with_spread_prob(0.4);
+            forest.ignite(Some((20, 20)), &mut rng);
+            while !forest.fire_spread(&wind, &mut rng) {}
+
+            let middle_row = forest.rows().nth(20).unwrap();
+            for (x, cell) in middle_row.iter().enumerate() {
+                if *cell == CellState::Burned {
+                    east = east.max(x.saturating_sub(20));
+                    west = west.max(20usize.saturating_sub(x));
+                }
+            }
+        }
+        assert!(east > west);
+    }
+
+    #[test]
+    fn test_fuel_keeps_cells_burning() {
+        let mut rng = rng_from_seed(0);
+        let mut forest = Forest::new(5, 1, 1.0, &mut rng).with_fuel(Fuel { burn_steps: 3, ..Fuel::default() });
+        forest.ignite(Some((0, 0)), &mut rng);
+
+        let mut steps = 0;
+        let mut peak = 0;
+        while !forest.fire_spread(&crate::fire_spread::VonNeumannNeighborhood, &mut rng) {
+            steps += 1;
+            peak = peak.max(forest.burning_count());
+        }
+        // the front advances one cell a step and the last tree burns for three
+        assert_eq!(steps + 1, 4 + 3);
+        assert_eq!(peak, 3);
+        assert_eq!(forest.burned_count, 5);
+    }
+
+    #[test]
+    fn test_spread_phase_delays_the_front() {
+        let mut rng = rng_from_seed(0);
+        let fuel = Fuel { burn_steps: 4, spread_from: 2, spread_until: 3 };
+        let mut forest = Forest::new(3, 1, 1.0, &mut rng).with_fuel(fuel);
+        forest.ignite(Some((0, 0)), &mut rng);
+
+        for _ in 0..2 {
+            forest.fire_spread(&crate::fire_spread::VonNeumannNeighborhood, &mut rng);
+            assert_eq!(forest.get(1, 0), CellState::Tree);
+        }
+        forest.fire_spread(&crate::fire_spread::VonNeumannNeighborhood, &mut rng);
+        assert_eq!(forest.get(1, 0), CellState::Burning);
+        assert_eq!(forest.get(0, 0), CellState::Burning);
+
+        // a fuel that never spreads leaves the neighbors standing
+        let mut forest = Forest::new(3, 1, 1.0, &mut rng).with_fuel(Fuel { burn_steps: 2, spread_from: 5, spread_until: 6 });
+        forest.ignite(Some((0, 0)), &mut rng);
+        while !forest.fire_spread(&crate::fire_spread::VonNeumannNeighborhood, &mut rng) {}
+        assert_eq!(forest.burned_count, 1);
+    }
+
+    #[test]
+    fn test_generate_places_every_class() {
+        let densities = [(Vegetation::Grass, 0.3), (Vegetation::Deciduous, 0.2), (Vegetation::Water, 0.1)];
+        let forest = Forest::generate(20, 10, &densities, &mut rng_from_seed(4));
+
+        let count = |class| forest.vegetation.iter().zip(forest.cells()).filter(|&(&v, _)| v == class).count();
+        assert_eq!(forest.class_trees()[Vegetation::Grass as usize], 60);
+        assert_eq!(forest.class_trees()[Vegetation::Deciduous as usize], 40);
+        assert_eq!(forest.total_trees, 100);
+        assert_eq!(count(Vegetation::Grass), 60);
+        assert_eq!(count(Vegetation::Water), 20);
+        assert_eq!(forest.cells().iter().filter(|&&c| c == CellState::Tree).count(), 100);
+
+        // conifers alone are the classic forest
+        let classic = Forest::new(20, 10, 0.5, &mut rng_from_seed(4));
+        let conifers = Forest::generate(20, 10, &[(Vegetation::Conifer, 0.5)], &mut rng_from_seed(4));
+        assert!(classic.cells() == conifers.cells());
+    }
+
+    #[test]
+    fn test_forest_from_map() {
+        let map = ForestMap::parse_ascii("TT.G\n*x~D\n").unwrap();
+        let mut forest = Forest::from_map(&map);
+        assert_eq!(forest.total_trees, 5);
+        assert_eq!(forest.class_trees()[Vegetation::Grass as usize], 1);
+        assert_eq!(forest.burning_count(), 1);
+        assert_eq!(forest.get(1, 1), CellState::Burned);
+        assert_eq!(forest.vegetation(2, 1), Vegetation::Water);
+
+        // the mapped fire spreads without a lightning strike
+        let mut rng = rng_from_seed(1);
+        while !forest.fire_spread(&crate::fire_spread::VonNeumannNeighborhood, &mut rng) {}
+        assert_eq!(forest.burned_count, 3);
+        assert_eq!(forest.get(3, 0), CellState::Tree);
+
+        let river = Forest::from_file(std::path::Path::new("maps/river.txt")).unwrap();
+        assert_eq!((river.width, river.height, river.total_trees), (12, 6, 60));
+        assert!(Forest::from_file(std::path::Path::new("maps/missing.txt")).is_err());
+    }
+
+    #[test]
+    fn test_clustered_generation_keeps_counts() {
+        let densities = [(Vegetation::Grass, 0.2), (Vegetation::Conifer, 0.35), (Vegetation::Rock, 0.1)];
+        let placement = Placement::Noise { scale: 6.0, octaves: 3 };
+        let forest = Forest::generate_with(40, 25, &densities, placement, &mut rng_from_seed(8));
+
+        assert_eq!(forest.total_trees, 550);
+        assert_eq!(forest.class_trees()[Vegetation::Grass as usize], 200);
+        assert_eq!(forest.cells().iter().filter(|&&c| c == CellState::Tree).count(), 550);
+        assert_eq!(forest.vegetation.iter().filter(|&&v| v == Vegetation::Rock).count(), 100);
+
+        let uniform = Forest::generate_with(40, 25, &densities, Placement::Uniform, &mut rng_from_seed(8));
+        let classic = Forest::generate(40, 25, &densities, &mut rng_from_seed(8));
+        assert!(uniform.cells() == classic.cells() && uniform.vegetation == classic.vegetation);
+
+        // rock listed first still fills the cells ranked last
+        let rock_first = [(Vegetation::Rock, 0.1), (Vegetation::Grass, 0.2), (Vegetation::Conifer, 0.35)];
+        let reordered = Forest::generate_with(40, 25, &rock_first, placement, &mut rng_from_seed(8));
+        assert!(reordered.cells() == forest.cells() && reordered.vegetation == forest.vegetation);
+    }
+
+    #[test]
+    fn test_fire_stops_at_water_and_counts_classes() {
+        let mut rng = rng_from_seed(0);
+        let mut forest = Forest::generate(5, 1, &[(Vegetation::Grass, 1.0)], &mut rng);
+        forest.vegetation[2] = Vegetation::Water;
+        forest.cells[2] = CellState::Empty;
+        assert_eq!(forest.glyph(2), "🌊");
+        assert_eq!(forest.glyph(0), "🌾");
+
+        forest.ignite(Some((0, 0)), &mut rng);
+        while !forest.fire_spread(&crate::fire_spread::VonNeumannNeighborhood, &mut rng) {}
+        assert_eq!(forest.burned_count, 2);
+        assert_eq!(forest.class_burned()[Vegetation::Grass as usize], 2);
+
+        // a class that never ignites is never reached
+        let mut classes = VegetationTable::default();
+        classes[Vegetation::Shrub].ignition = 0.0;
+        let mut forest = Forest::generate(5, 1, &[(Vegetation::Shrub, 1.0)], &mut rng).with_classes(classes);
+        forest.ignite(Some((0, 0)), &mut rng);
+        while !forest.fire_spread(&crate::fire_spread::MooreNeighborhood, &mut rng) {}
+        assert_eq!(forest.burned_count, 1);
+    }
+
+    #[test]
+    fn test_regrowth_after_fire() {
+        let mut rng = rng_from_seed(6);
+        let mut forest = Forest::generate(30, 30, &[(Vegetation::Conifer, 0.5), (Vegetation::Rock, 0.2)], &mut rng);
+        let strike = forest.pick_random_tree(&mut rng);
+        forest.ignite(strike, &mut rng);
+        while !forest.fire_spread(&crate::fire_spread::MooreNeighborhood, &mut rng) {}
+
+        forest.clear_burned();
+        assert!(!forest.cells().contains(&CellState::Burned));
+
+        // certain growth fills every empty cell except the rock
+        let empty = forest.cells().iter().filter(|&&c| c == CellState::Empty).count();
+        assert_eq!(forest.regrow(1.0, &mut rng), empty - 180);
+        assert_eq!(forest.regrow(1.0, &mut rng), 0);
+        assert_eq!(forest.cells().iter().filter(|&&c| c == CellState::Tree).count(), 720);
+
+        // regrown trees burn like new ones
+        forest.ignite(Some(forest.pick_random_tree(&mut rng).unwrap()), &mut rng);
+        while !forest.fire_spread(&crate::fire_spread::MooreNeighborhood, &mut rng) {}
+        assert!(forest.cells().contains(&CellState::Burned));
+    }
+
+    #[test]
+    fn test_burned_cells_regrow_with_age() {
+        let mut rng = rng_from_seed(3);
+        let mut forest = Forest::new(20, 20, 1.0, &mut rng);
+        forest.ignite(Some((10, 10)), &mut rng);
+        while !forest.fire_spread(&crate::fire_spread::MooreNeighborhood, &mut rng) {}
+        assert_eq!(forest.tree_count(), 0);
+
+        // nothing regrows in the year of the fire, everything once a year has passed
+        let chance = |years: u16| if years >= 1 { 1.0 } else { 0.0 };
+        assert_eq!(forest.regrow_burned(&mut rng, chance), 0);
+        forest.age_one_year();
+        assert_eq!(forest.age(0, 0), 1);
+        assert_eq!(forest.regrow_burned(&mut rng, chance), 400);
+        assert_eq!(forest.tree_count(), 400);
+        assert_eq!(forest.age(0, 0), 0);
+
+        forest.age_one_year();
+        forest.age_one_year();
+        assert_eq!(forest.age(5, 5), 2);
+    }
+
+    #[test]
+    fn test_cell_sampling_rate() {
+        let mut rng = rng_from_seed(10);
+        let mut hits = 0;
+        let mut last = None;
+        sample_cells(100_000, 0.01, &mut rng, |index| {
+            assert!(last.is_none_or(|l| index > l));
+            last = Some(index);
+            hits += 1;
+        });
+        assert!((800..1200).contains(&hits));
+
+        let forest = Forest::new(10, 10, 1.0, &mut rng);
+        assert_eq!(forest.lightning_strikes(1.0, &mut rng).len(), 100);
+        assert!(forest.lightning_strikes(0.0, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn test_cell_accessors() {
+        let mut forest = Forest::new(4, 3, 0.0, &mut rng_from_seed(0));
+        forest.set(3, 1, CellState::Tree);
+
+        assert_eq!(forest.get(3, 1), CellState::Tree);
+        assert_eq!(forest.cells()[forest.index(3, 1)], CellState::Tree);
+        assert_eq!(forest.coords(7), (3, 1));
+        assert_eq!(forest.rows().nth(1).unwrap()[3], CellState::Tree);
+
+        let mut trees = Vec::new();
+        forest.for_each_neighbor(2, 2, &crate::fire_spread::MooreNeighborhood, &mut |pos, state| {
+            if state == CellState::Tree {
+                trees.push(pos);
+            }
+        });
+        assert_eq!(trees, vec![(3, 1)]);
+        let mut count = 0;
+        forest.for_each_neighbor(0, 0, &crate::fire_spread::VonNeumannNeighborhood, &mut |_, _| count += 1);
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_arrival_times() {
+        let mut rng = rng_from_seed(2);
+        let mut forest = Forest::generate(6, 1, &[(Vegetation::Conifer, 1.0)], &mut rng);
+        forest.set(4, 0, CellState::Empty);
+        forest.ignite(Some((1, 0)), &mut rng);
+        while !forest.fire_spread(&crate::fire_spread::VonNeumannNeighborhood, &mut rng) {}
+
+        let arrival: Vec<_> = (0..6).map(|x| forest.arrival(x, 0)).collect();
+        assert_eq!(arrival, vec![Some(1), Some(0), Some(1), Some(2), None, None]);
+        assert_eq!(forest.clock(), 3);
+
+        // a regrown tree has not burned yet
+        forest.regrow_burned(&mut rng, |_| 1.0);
+        assert_eq!(forest.arrival(1, 0), None);
+    }
+}
@@ -2,4 +2,20 @@ pub mod config;
 pub mod simulation;
 pub mod fire_spread;
 pub mod forest;
-pub mod display;
\ No newline at end of file
+pub mod display;
+pub mod output;
+pub mod statistics;
+pub mod terrain;
+pub mod vegetation;
+pub mod drossel_schwabl;
+pub mod seasons;
+pub mod placement;
+pub mod map;
+pub mod snapshot;
+pub mod recording;
+pub mod arrival;
+pub mod clusters;
+pub mod spanning;
+pub mod threshold;
+pub mod scaling;
+pub mod sweep;
@@ -1,12 +1,13 @@
-mod config;
-mod forest;
-mod simulation;
-mod fire_spread;
-mod display;
-
+use std::io::{stdout, Write};
 use std::time::Instant;
-use config::Config;
-use simulation::run_simulations;
+use rand::Rng;
+use project_forest_fire::config::{Config, Model};
+use project_forest_fire::drossel_schwabl::run_drossel_schwabl;
+use project_forest_fire::output::{write_fire_sizes, write_results, write_scaling, write_seasons, write_threshold, SweepWriter};
+use project_forest_fire::scaling::run_scaling;
+use project_forest_fire::seasons::run_seasons;
+use project_forest_fire::simulation::run_simulations;
+use project_forest_fire::threshold::find_threshold;
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
@@ -18,54 +19,47 @@ fn main() {
         }
     };
     let start = Instant::now();
-    if config.auto_sweep {
-        // Handle missing step parameter
-        let step = config.sweep_step.unwrap_or_else(|| {
-            eprintln!("Auto sweep requires a step size. Using default 0.1");
-            0.1
-        });
-
-        // Loop through densities from 0.0 to 1.0 in increments
-        let mut density = 0.01;
-        while density <= 1.0 {
-            // Create a new config with current density
-            let sweep_config = Config {
-                density,
-                ..config.clone()
-            };
-
-            let results = run_simulations(&sweep_config);
-
-            // Print density and result for data collection
-            println!("density: {:.2}, average burned: {:.5} across {} simulaitons", density, results.average_burned, config.simulations);
-
-            // Increment density, handling floating-point precision
-            density = (density + step).min(1.0);
+    let mut out = stdout().lock();
+    if let Some(study) = &config.scaling {
+        let results = run_scaling(&config, study);
+        write_scaling(&mut out, &config, &results, start.elapsed()).unwrap();
+        return;
+    }
+    if let Some(sweep) = &config.sweep {
+        // one master seed for the whole sweep, so every point can be reproduced
+        let config = Config {
+            seed: Some(config.seed.unwrap_or_else(|| rand::rng().random())),
+            ..config.clone()
+        };
+        let mut writer = SweepWriter::new(&mut out, &config, sweep).unwrap();
 
-            // Break if we've reached 1.0 or step is too small
-            if (density - 1.0).abs() < f64::EPSILON || step < f64::EPSILON {
-                break;
-            }
+        // one batch for every combination of the swept values
+        for point in sweep.combinations(&config) {
+            let point_start = Instant::now();
+            let results = run_simulations(&point);
+            writer.row(&point, &results, point_start.elapsed()).unwrap();
         }
-        let elapsed = start.elapsed();
-        println!("Elapsed time: {:.2?}", elapsed);
+        writer.finish(start.elapsed()).unwrap();
+        out.flush().unwrap();
         std::process::exit(0); // Exit after sweep completes
     }
 
-    let results = run_simulations(&config);
-
-    if config.quiet {
-        println!("{:.5}", results.average_burned);
-    } else {
-        println!("Simulation Results:");
-        println!("-------------------");
-        println!("Grid size: {}", config.size);
-        println!("Tree density: {:.2}", config.density);
-        println!("Burn pattern: {:?}", config.burn_pattern);
-        println!("Min burned: {:.2}%", results.min_burned);
-        println!("Max burned: {:.2}%", results.max_burned);
-        println!("Average burned: {:.2}%", results.average_burned);
-        let elapsed = start.elapsed();
-        println!("Elapsed time: {:.2?}", elapsed);
+    match config.model {
+        Model::SingleFire => {
+            let results = run_simulations(&config);
+            write_results(&mut out, &config, &results, start.elapsed()).unwrap();
+        }
+        Model::DrosselSchwabl(model) => {
+            let results = run_drossel_schwabl(&config, model);
+            write_fire_sizes(&mut out, &config, model, &results, start.elapsed()).unwrap();
+        }
+        Model::Seasons(model) => {
+            let results = run_seasons(&config, model);
+            write_seasons(&mut out, &config, model, &results, start.elapsed()).unwrap();
+        }
+        Model::Threshold(model) => {
+            let results = find_threshold(&config, model);
+            write_threshold(&mut out, &config, model, &results, start.elapsed()).unwrap();
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,340 @@
+use std::fs;
+use std::path::Path;
+use crate::forest::CellState;
+use crate::terrain::read_pgm;
+use crate::vegetation::Vegetation;
+
+/// Symbols of an ASCII map. Letters may also be lowercase; empty cells are
+/// conifer ground like in a generated forest, burning cells conifers.
+const SYMBOLS: [(char, CellState, Vegetation); 10] = [
+    ('.', CellState::Empty, Vegetation::Conifer),
+    ('T', CellState::Tree, Vegetation::Conifer),
+    ('C', CellState::Tree, Vegetation::Conifer),
+    ('D', CellState::Tree, Vegetation::Deciduous),
+    ('S', CellState::Tree, Vegetation::Shrub),
+    ('G', CellState::Tree, Vegetation::Grass),
+    ('~', CellState::Empty, Vegetation::Water),
+    ('R', CellState::Empty, Vegetation::Rock),
+    ('*', CellState::Burning, Vegetation::Conifer),
+    ('x', CellState::Burned, Vegetation::Conifer),
+];
+
+/// Colors of a color map, matched to the nearest entry.
+const PALETTE: [([u8; 3], CellState, Vegetation); 9] = [
+    ([255, 255, 255], CellState::Empty, Vegetation::Conifer),
+    ([20, 100, 30], CellState::Tree, Vegetation::Conifer),
+    ([60, 160, 60], CellState::Tree, Vegetation::Deciduous),
+    ([110, 150, 40], CellState::Tree, Vegetation::Shrub),
+    ([170, 210, 60], CellState::Tree, Vegetation::Grass),
+    ([30, 110, 220], CellState::Empty, Vegetation::Water),
+    ([128, 128, 128], CellState::Empty, Vegetation::Rock),
+    ([230, 60, 20], CellState::Burning, Vegetation::Conifer),
+    ([30, 30, 30], CellState::Burned, Vegetation::Conifer),
+];
+
+/// Magic number of a binary grid: then width and height as little-endian `u32`
+/// and one byte per cell, row-major, from [`encode_cell`].
+pub const BINARY_MAGIC: &[u8; 4] = b"FFG1";
+
+/// A forest layout read from a file: the state and vegetation class of every cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForestMap {
+    pub width: usize,
+    pub height: usize,
+    pub source: String, // file the map was read from
+    cells: Vec<(CellState, Vegetation)>,
+}
+
+impl ForestMap {
+    /// Loads a map, the format told by its content or extension:
+    /// - PGM (`P2`/`P5`) or grayscale PNG: dark pixels are trees, light ones empty ground;
+    /// - color PNG: each pixel takes the nearest color of the palette (see [`color`]);
+    /// - `.csv`: comma-separated cells, each a map symbol, a class name or a state code
+    ///   (0 empty, 1 tree, 2 burning, 3 burned);
+    /// - a binary grid starting with [`BINARY_MAGIC`], as written by the snapshots;
+    /// - anything else: an ASCII layout, one symbol per cell (see [`symbol`]).
+    ///
+    /// Text formats skip blank lines and lines starting with `#`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| format!("Cannot read map {}: {}", path.display(), e))?;
+        let mut map = match bytes.get(..4) {
+            Some(magic) if magic.starts_with(b"P2") || magic.starts_with(b"P5") => Self::parse_pgm(&bytes),
+            Some(b"\x89PNG") => Self::parse_png(&bytes),
+            Some(magic) if magic == BINARY_MAGIC => Self::parse_binary(&bytes),
+            _ if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("csv")) => {
+                Self::parse_csv(&String::from_utf8_lossy(&bytes))
+            }
+            _ => Self::parse_ascii(&String::from_utf8_lossy(&bytes)),
+        }
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+        map.source = path.display().to_string();
+        Ok(map)
+    }
+
+    pub fn from_cells(width: usize, height: usize, cells: Vec<(CellState, Vegetation)>) -> Result<Self, String> {
+        if width == 0 || height == 0 || cells.len() != width * height {
+            return Err(format!("Map needs {}x{} cells, got {}", width, height, cells.len()));
+        }
+        Ok(ForestMap { width, height, source: String::new(), cells })
+    }
+
+    pub fn parse_ascii(text: &str) -> Result<Self, String> {
+        Self::parse_rows(text, |line| {
+            line.trim_end()
+                .chars()
+                .map(|c| parse_symbol(c).ok_or_else(|| format!("Unknown map symbol '{}'", c)))
+                .collect()
+        })
+    }
+
+    pub fn parse_csv(text: &str) -> Result<Self, String> {
+        Self::parse_rows(text, |line| {
+            line.split(',')
+                .map(|field| {
+                    let field = field.trim();
+                    let mut chars = field.chars();
+                    let symbol = match (chars.next(), chars.next()) {
+                        (Some(c), None) => parse_symbol(c),
+                        _ => None,
+                    };
+                    let state = match field {
+                        "0" => Some((CellState::Empty, Vegetation::Conifer)),
+                        "1" => Some((CellState::Tree, Vegetation::Conifer)),
+                        "2" => Some((CellState::Burning, Vegetation::Conifer)),
+                        "3" => Some((CellState::Burned, Vegetation::Conifer)),
+                        _ => None,
+                    };
+                    let class = Vegetation::parse(field)
+                        .map(|class| (if class.is_fuel() { CellState::Tree } else { CellState::Empty }, class));
+                    state.or(symbol).or(class).ok_or_else(|| format!("Unknown map cell '{}'", field))
+                })
+                .collect()
+        })
+    }
+
+    fn parse_rows(
+        text: &str,
+        parse_row: impl Fn(&str) -> Result<Vec<(CellState, Vegetation)>, String>,
+    ) -> Result<Self, String> {
+        let rows: Vec<Vec<(CellState, Vegetation)>> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(parse_row)
+            .collect::<Result<_, _>>()?;
+
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return Err("Map rows must all have the same length".into());
+        }
+        Self::from_cells(width, rows.len(), rows.concat())
+    }
+
+    fn parse_pgm(bytes: &[u8]) -> Result<Self, String> {
+        let (width, height, maxval, values) = read_pgm(bytes)?;
+        let cells = values.into_iter().map(|v| gray_cell(v / maxval.max(1) as f64)).collect();
+        Self::from_cells(width, height, cells)
+    }
+
+    fn parse_png(bytes: &[u8]) -> Result<Self, String> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info().map_err(|e| format!("Invalid PNG: {}", e))?;
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut pixels).map_err(|e| format!("Invalid PNG: {}", e))?;
+        let (width, height) = (frame.width as usize, frame.height as usize);
+
+        let channels = frame.color_type.samples();
+        let cells = pixels[..frame.buffer_size()]
+            .chunks_exact(channels)
+            .map(|pixel| match frame.color_type {
+                // transparent pixels are bare ground
+                png::ColorType::GrayscaleAlpha | png::ColorType::Rgba if pixel[channels - 1] < 128 => {
+                    (CellState::Empty, Vegetation::Conifer)
+                }
+                png::ColorType::Grayscale | png::ColorType::GrayscaleAlpha => gray_cell(pixel[0] as f64 / 255.0),
+                _ => nearest_color([pixel[0], pixel[1], pixel[2]]),
+            })
+            .collect();
+        Self::from_cells(width, height, cells)
+    }
+
+    fn parse_binary(bytes: &[u8]) -> Result<Self, String> {
+        let field = |at: usize| bytes.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize);
+        let (Some(width), Some(height)) = (field(4), field(8)) else {
+            return Err("Truncated binary grid header".into());
+        };
+        let cells = bytes[12..]
+            .iter()
+            .map(|&byte| decode_cell(byte).ok_or_else(|| format!("Invalid binary cell {:#04x}", byte)))
+            .collect::<Result<_, _>>()?;
+        Self::from_cells(width, height, cells)
+    }
+
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> (CellState, Vegetation) {
+        self.cells[y * self.width + x]
+    }
+
+    /// Trees (standing or burning) of every fuel class and cells of water and rock.
+    pub fn class_counts(&self) -> [usize; Vegetation::COUNT] {
+        let mut counts = [0; Vegetation::COUNT];
+        for &(state, class) in &self.cells {
+            if matches!(state, CellState::Tree | CellState::Burning) || !class.is_fuel() {
+                counts[class as usize] += 1;
+            }
+        }
+        counts
+    }
+
+    /// Whether the map holds a fire front.
+    pub fn is_burning(&self) -> bool {
+        self.cells.iter().any(|&(state, _)| state == CellState::Burning)
+    }
+}
+
+/// ASCII map symbol of a cell.
+pub fn symbol(state: CellState, vegetation: Vegetation) -> char {
+    match (state, vegetation) {
+        (CellState::Tree, Vegetation::Conifer) => 'T',
+        (CellState::Tree, Vegetation::Deciduous) => 'D',
+        (CellState::Tree, Vegetation::Shrub) => 'S',
+        (CellState::Tree, Vegetation::Grass) => 'G',
+        (CellState::Burning, _) => '*',
+        (CellState::Burned, _) => 'x',
+        (_, Vegetation::Water) => '~',
+        (_, Vegetation::Rock) => 'R',
+        _ => '.',
+    }
+}
+
+/// Color of a cell in a color map.
+pub fn color(state: CellState, vegetation: Vegetation) -> [u8; 3] {
+    PALETTE[palette_index(state, vegetation)].0
+}
+
+/// Index of the color of a cell in [`palette`].
+pub fn palette_index(state: CellState, vegetation: Vegetation) -> usize {
+    let target = match (state, vegetation) {
+        (CellState::Tree, class) => (CellState::Tree, class),
+        (CellState::Empty, class) if !class.is_fuel() => (CellState::Empty, class),
+        (state, _) => (state, Vegetation::Conifer),
+    };
+    PALETTE.iter().position(|&(_, s, v)| (s, v) == target).unwrap_or(0)
+}
+
+/// The map colors as consecutive RGB bytes, e.g. for an indexed image.
+pub fn palette() -> Vec<u8> {
+    PALETTE.iter().flat_map(|&(rgb, _, _)| rgb).collect()
+}
+
+/// Byte of a cell in a binary grid: the state in the low two bits, the class above.
+pub fn encode_cell(state: CellState, vegetation: Vegetation) -> u8 {
+    state as u8 | (vegetation as u8) << 2
+}
+
+fn decode_cell(byte: u8) -> Option<(CellState, Vegetation)> {
+    let state = [CellState::Empty, CellState::Tree, CellState::Burning, CellState::Burned][(byte & 0b11) as usize];
+    Vegetation::ALL.get((byte >> 2) as usize).map(|&vegetation| (state, vegetation))
+}
+
+fn parse_symbol(c: char) -> Option<(CellState, Vegetation)> {
+    SYMBOLS
+        .iter()
+        .find(|&&(symbol, _, _)| symbol == c || symbol == c.to_ascii_uppercase())
+        .map(|&(_, state, vegetation)| (state, vegetation))
+}
+
+/// A gray level from 0.0 (black) to 1.0 (white): dark is a tree.
+fn gray_cell(level: f64) -> (CellState, Vegetation) {
+    let state = if level < 0.5 { CellState::Tree } else { CellState::Empty };
+    (state, Vegetation::Conifer)
+}
+
+fn nearest_color(rgb: [u8; 3]) -> (CellState, Vegetation) {
+    let distance = |other: [u8; 3]| (0..3).map(|i| (rgb[i] as i32 - other[i] as i32).pow(2)).sum::<i32>();
+    PALETTE
+        .iter()
+        .min_by_key(|&&(other, _, _)| distance(other))
+        .map(|&(_, state, vegetation)| (state, vegetation))
+        .unwrap()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_symbols_round_trip() {
+        let map = ForestMap::parse_ascii("# stand 7\nTTg.~\nd*xRs\n").unwrap();
+        assert_eq!((map.width, map.height), (5, 2));
+        assert_eq!(map.get(2, 0), (CellState::Tree, Vegetation::Grass));
+        assert_eq!(map.get(4, 0), (CellState::Empty, Vegetation::Water));
+        assert_eq!(map.get(1, 1), (CellState::Burning, Vegetation::Conifer));
+        for y in 0..map.height {
+            for x in 0..map.width {
+                let (state, vegetation) = map.get(x, y);
+                assert_eq!(parse_symbol(symbol(state, vegetation)), Some(map.get(x, y)));
+                assert_eq!(nearest_color(color(state, vegetation)), map.get(x, y));
+            }
+        }
+
+        let counts = map.class_counts();
+        assert_eq!(counts[Vegetation::Conifer as usize], 3);
+        assert_eq!(counts[Vegetation::Water as usize], 1);
+        assert!(ForestMap::parse_ascii("TT\nT\n").is_err());
+        assert!(ForestMap::parse_ascii("T?\n").is_err());
+    }
+
+    #[test]
+    fn test_csv_accepts_codes_names_and_symbols() {
+        let map = ForestMap::parse_csv("1, 0, water\nshrub,*,3\n").unwrap();
+        assert_eq!((map.width, map.height), (3, 2));
+        assert_eq!(map.get(0, 0), (CellState::Tree, Vegetation::Conifer));
+        assert_eq!(map.get(2, 0), (CellState::Empty, Vegetation::Water));
+        assert_eq!(map.get(0, 1), (CellState::Tree, Vegetation::Shrub));
+        assert_eq!(map.get(1, 1).0, CellState::Burning);
+        assert_eq!(map.get(2, 1).0, CellState::Burned);
+        assert!(ForestMap::parse_csv("1,7\n").is_err());
+    }
+
+    #[test]
+    fn test_binary_cells() {
+        for state in [CellState::Empty, CellState::Tree, CellState::Burning, CellState::Burned] {
+            for vegetation in Vegetation::ALL {
+                assert_eq!(decode_cell(encode_cell(state, vegetation)), Some((state, vegetation)));
+            }
+        }
+        let mut bytes = BINARY_MAGIC.to_vec();
+        bytes.extend(2u32.to_le_bytes());
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend([encode_cell(CellState::Tree, Vegetation::Shrub), encode_cell(CellState::Burned, Vegetation::Grass)]);
+        let map = ForestMap::parse_binary(&bytes).unwrap();
+        assert_eq!(map.get(1, 0), (CellState::Burned, Vegetation::Grass));
+
+        assert!(ForestMap::parse_binary(&bytes[..13]).is_err());
+        assert!(ForestMap::parse_binary(&bytes[..6]).is_err());
+        bytes.push(0xff);
+        assert!(ForestMap::parse_binary(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_images() {
+        let pgm = ForestMap::parse_pgm(b"P2 3 1 255\n0 200 90\n").unwrap();
+        let states: Vec<_> = (0..3).map(|x| pgm.get(x, 0).0).collect();
+        assert_eq!(states, vec![CellState::Tree, CellState::Empty, CellState::Tree]);
+
+        // a 2x1 color PNG: a deciduous tree next to water
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, 2, 1);
+        encoder.set_color(png::ColorType::Rgb);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[70, 150, 60, 20, 100, 230]).unwrap();
+        writer.finish().unwrap();
+
+        let map = ForestMap::parse_png(&bytes).unwrap();
+        assert_eq!(map.get(0, 0), (CellState::Tree, Vegetation::Deciduous));
+        assert_eq!(map.get(1, 0), (CellState::Empty, Vegetation::Water));
+        assert!(ForestMap::parse_png(b"\x89PNG broken").is_err());
+    }
+}
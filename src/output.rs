@@ -0,0 +1,1204 @@
+use std::io::{self, Write};
+use std::time::Duration;
+use crate::config::{BurnPattern, Config, OutputFormat};
+use crate::drossel_schwabl::{DrosselSchwabl, FireSizeResults};
+use crate::fire_spread::Boundary;
+use crate::placement::Placement;
+use crate::scaling::{Estimate, ScalingResults};
+use crate::seasons::{age_class_labels, Recovery, SeasonResults, Seasons};
+use crate::simulation::SimulationResults;
+use crate::statistics::Proportion;
+use crate::sweep::{Axis, Sweep};
+use crate::terrain::TerrainSource;
+use crate::threshold::{Criterion, Threshold, ThresholdResults};
+use crate::vegetation::Vegetation;
+
+const CSV_SUMMARY_HEADER: &str = "width,height,density,spread_prob,vegetation,placement,burn_pattern,boundary,terrain,simulations,seed,\
+    min_burned,max_burned,average_burned,runs,skipped,mean_burned,std_dev,median,\
+    p05,p25,p75,p95,ci95,mean_steps,max_steps,mean_peak_front,max_peak_front,mean_spread_rate,\
+    mean_clusters,mean_largest_cluster,mean_cluster_size,\
+    spanning,spanning_ci_low,spanning_ci_high,spanning_left_right,spanning_top_bottom,spanning_both,wrapping,percolation,\
+    grass_burned,shrub_burned,conifer_burned,deciduous_burned,elapsed_seconds";
+const CSV_FIRE_SIZES_HEADER: &str = "size_min,size_max,fires,frequency";
+const CSV_SEASONS_HEADER: &str = "run,season,trees_before,burned,burned_percent,cover_before,cover_after,mean_age,\
+    age_0_9,age_10_19,age_20_49,age_50_plus";
+const CSV_THRESHOLD_HEADER: &str = "criterion,target,tolerance,estimate,std_error,low,high,slope,simulations,seed,elapsed_seconds";
+const CSV_THRESHOLD_POINTS_HEADER: &str = "density,value,std_error";
+const CSV_SCALING_POINTS_HEADER: &str = "size,density,percolation,percolation_ci_low,percolation_ci_high,spanning,\
+    mean_burned,mean_largest_cluster,runs";
+const CSV_SCALING_SIZES_HEADER: &str = "size,crossing,mean,width";
+const CSV_SCALING_FIT_HEADER: &str = "critical_density,critical_density_std_error,nu,nu_std_error,simulations,seed,elapsed_seconds";
+const CSV_RUNS_HEADER: &str = "run,seed,total_trees,burned_count,burned_percent,steps,peak_front,spread_rate,\
+    clusters,largest_cluster,cluster_size,percolates,spans_left_right,spans_top_bottom,wraps,ignition_x,ignition_y";
+
+/// Writes the results of one batch of simulations in the configured format.
+///
+/// Text is the classic human-readable report (or a bare average with `--quiet`),
+/// JSON is one object with the config echo, summary and per-run records (when kept),
+/// CSV is a one-row summary table followed by a blank line and the per-run table (when kept).
+pub fn write_results(
+    out: &mut dyn Write,
+    config: &Config,
+    results: &SimulationResults,
+    elapsed: Duration,
+) -> io::Result<()> {
+    match config.output_format {
+        OutputFormat::Text if config.quiet => {
+            writeln!(out, "{:.5}", results.average_burned)
+        }
+        OutputFormat::Text => {
+            writeln!(out, "Simulation Results:")?;
+            writeln!(out, "-------------------")?;
+            writeln!(out, "Grid size: {}x{}", config.width, config.height)?;
+            writeln!(out, "Tree density: {:.2}", config.density)?;
+            writeln!(out, "Spread probability: {:.2}", config.spread_prob)?;
+            for (class, density) in config.vegetation.densities(config.density) {
+                if class.is_fuel() {
+                    let fuel = config.classes[class].fuel;
+                    writeln!(
+                        out,
+                        "Vegetation: {} {:.2} (ignition {:.2}, burns {} steps, spreading in steps {}-{})",
+                        class.name(), density, config.classes[class].ignition,
+                        fuel.burn_steps, fuel.spread_from + 1, fuel.spread_until.min(fuel.burn_steps)
+                    )?;
+                } else {
+                    writeln!(out, "Vegetation: {} {:.2}", class.name(), density)?;
+                }
+            }
+            if let Some(map) = &config.map {
+                writeln!(out, "Map: {}", map.source)?;
+            } else if config.placement != Placement::Uniform {
+                writeln!(out, "Placement: {}", placement_details(config.placement))?;
+            }
+            writeln!(out, "Burn pattern: {:?}", config.burn_pattern)?;
+            writeln!(out, "Boundary: {}", config.boundary.name())?;
+            if !matches!(config.terrain, TerrainSource::Flat) {
+                writeln!(
+                    out,
+                    "Terrain: {} (relief {}, slope factor {})",
+                    config.terrain.name(), config.relief, config.slope_factor
+                )?;
+            }
+            writeln!(out, "Seed: {}", results.seed)?;
+            writeln!(out, "Min burned: {:.2}%", results.min_burned)?;
+            writeln!(out, "Max burned: {:.2}%", results.max_burned)?;
+            writeln!(out, "Average burned: {:.2}%", results.average_burned)?;
+            let stats = &results.stats;
+            writeln!(out, "Mean burned per run: {:.2}% ± {:.2}% (95% CI)", stats.mean, stats.ci95)?;
+            writeln!(out, "Std deviation: {:.2}%", stats.std_dev)?;
+            writeln!(out, "Median burned: {:.2}%", stats.median)?;
+            writeln!(
+                out,
+                "Percentiles (5/25/75/95): {:.2}% / {:.2}% / {:.2}% / {:.2}%",
+                stats.p05, stats.p25, stats.p75, stats.p95
+            )?;
+            writeln!(out, "Fire duration: {:.1} steps on average, {} at most", stats.mean_steps, stats.max_steps)?;
+            writeln!(
+                out,
+                "Peak fire front: {:.1} burning cells on average, {} at most",
+                stats.mean_peak_front, stats.max_peak_front
+            )?;
+            if stats.mean_spread_rate.is_finite() {
+                writeln!(out, "Rate of spread: {:.3} cells per step on average", stats.mean_spread_rate)?;
+            }
+            if stats.percolation.runs > 0 {
+                writeln!(
+                    out,
+                    "Tree clusters: {:.1} on average, largest {:.2}% of the trees, mean size {:.2}",
+                    stats.mean_clusters, stats.mean_largest_cluster, stats.mean_cluster_size
+                )?;
+            }
+            let (low, high) = stats.spanning.interval();
+            writeln!(
+                out,
+                "Spanning: {:.2}% of the runs (95% CI {:.2}% to {:.2}%); left-right {:.2}%, top-bottom {:.2}%, both {:.2}%",
+                stats.spanning.estimate() * 100.0, low * 100.0, high * 100.0,
+                stats.spanning_left_right.estimate() * 100.0,
+                stats.spanning_top_bottom.estimate() * 100.0,
+                stats.spanning_both.estimate() * 100.0
+            )?;
+            if config.boundary == Boundary::Periodic {
+                let (low, high) = stats.wrapping.interval();
+                writeln!(
+                    out,
+                    "Wrapping: {:.2}% of the runs (95% CI {:.2}% to {:.2}%)",
+                    stats.wrapping.estimate() * 100.0, low * 100.0, high * 100.0
+                )?;
+            }
+            if stats.percolation.runs > 0 {
+                let (low, high) = stats.percolation.interval();
+                writeln!(
+                    out,
+                    "Percolation: {:.2}% of the forests had a spanning cluster (95% CI {:.2}% to {:.2}%)",
+                    stats.percolation.estimate() * 100.0, low * 100.0, high * 100.0
+                )?;
+            }
+            let burned_by_class: Vec<String> = fuel_classes()
+                .filter(|&class| stats.class_trees[class as usize] > 0)
+                .map(|class| format!("{} {:.2}%", class.name(), stats.class_burned_percent(class)))
+                .collect();
+            if !burned_by_class.is_empty() {
+                writeln!(out, "Burned by class: {}", burned_by_class.join(", "))?;
+            }
+            writeln!(out, "Runs: {} ({} skipped without trees)", stats.runs, stats.skipped)?;
+            writeln!(out, "Elapsed time: {:.2?}", elapsed)
+        }
+        OutputFormat::Json => {
+            writeln!(out, "{{")?;
+            writeln!(out, "  \"config\": {},", json_config(config, results.seed))?;
+            writeln!(out, "  \"min_burned\": {},", json_number(results.min_burned))?;
+            writeln!(out, "  \"max_burned\": {},", json_number(results.max_burned))?;
+            writeln!(out, "  \"average_burned\": {},", json_number(results.average_burned))?;
+            writeln!(out, "  \"statistics\": {},", json_statistics(results))?;
+            let cluster_sizes: Vec<String> = results.cluster_sizes.iter().map(|(size, count)| format!("[{}, {}]", size, count)).collect();
+            writeln!(out, "  \"cluster_sizes\": [{}],", cluster_sizes.join(", "))?;
+            write!(out, "  \"elapsed_seconds\": {}", elapsed.as_secs_f64())?;
+            if let Some(runs) = &results.runs {
+                writeln!(out, ",")?;
+                writeln!(out, "  \"runs\": [")?;
+                for (i, run) in runs.iter().enumerate() {
+                    let separator = if i + 1 < runs.len() { "," } else { "" };
+                    let clusters = run.clusters.map_or_else(
+                        || ["null".to_string(), "null".into(), "null".into(), "null".into()],
+                        |c| [c.count.to_string(), c.largest.to_string(), json_number(c.mean_size), c.percolates.to_string()],
+                    );
+                    writeln!(
+                        out,
+                        "    {{\"index\": {}, \"seed\": {}, \"total_trees\": {}, \"burned_count\": {}, \"burned_percent\": {}, \"steps\": {}, \"peak_front\": {}, \"spread_rate\": {}, \"clusters\": {}, \"largest_cluster\": {}, \"cluster_size\": {}, \"percolates\": {}, \"spans_left_right\": {}, \"spans_top_bottom\": {}, \"wraps\": {}, \"ignition\": [{}, {}]}}{}",
+                        run.index, run.seed, run.total_trees, run.burned_count,
+                        json_number(run.burned_percent()), run.steps, run.peak_front,
+                        json_number(run.spread_rate.unwrap_or(f64::NAN)), clusters[0], clusters[1],
+                        clusters[2], clusters[3], run.spanning.left_right, run.spanning.top_bottom,
+                        run.spanning.wraps(), run.ignition.0, run.ignition.1, separator
+                    )?;
+                }
+                write!(out, "  ]")?;
+            }
+            writeln!(out)?;
+            writeln!(out, "}}")
+        }
+        OutputFormat::Csv => {
+            writeln!(out, "{}", CSV_SUMMARY_HEADER)?;
+            writeln!(out, "{}", csv_summary_row(config, results, elapsed))?;
+            if let Some(runs) = &results.runs {
+                writeln!(out)?;
+                writeln!(out, "{}", CSV_RUNS_HEADER)?;
+                for run in runs {
+                    let clusters = run.clusters.map_or_else(
+                        || ",,,".to_string(),
+                        |c| format!("{},{},{},{}", c.count, c.largest, c.mean_size, c.percolates),
+                    );
+                    writeln!(
+                        out,
+                        "{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                        run.index, run.seed, run.total_trees, run.burned_count, run.burned_percent(),
+                        run.steps, run.peak_front, run.spread_rate.map_or_else(String::new, |r| r.to_string()),
+                        clusters,
+                        run.spanning.left_right, run.spanning.top_bottom, run.spanning.wraps(), run.ignition.0, run.ignition.1
+                    )?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Writes the fire-size distribution of a Drossel–Schwabl batch in the configured format.
+///
+/// Text is a summary and the histogram table (or the fitted exponent alone with `--quiet`),
+/// JSON one object with the config echo, summary and histogram, CSV the histogram table.
+pub fn write_fire_sizes(
+    out: &mut dyn Write,
+    config: &Config,
+    model: DrosselSchwabl,
+    results: &FireSizeResults,
+    elapsed: Duration,
+) -> io::Result<()> {
+    match config.output_format {
+        OutputFormat::Text if config.quiet => {
+            writeln!(out, "{:.5}", results.tau)
+        }
+        OutputFormat::Text => {
+            writeln!(out, "Drossel–Schwabl Results:")?;
+            writeln!(out, "------------------------")?;
+            writeln!(out, "Grid size: {}x{}", config.width, config.height)?;
+            writeln!(out, "Growth probability: {}", model.growth)?;
+            writeln!(out, "Lightning probability: {}", model.lightning)?;
+            if let Some(map) = &config.map {
+                writeln!(out, "Map: {}", map.source)?;
+            } else if config.placement != Placement::Uniform {
+                writeln!(out, "Placement: {}", placement_details(config.placement))?;
+            }
+            writeln!(out, "Burn pattern: {:?}", config.burn_pattern)?;
+            writeln!(out, "Boundary: {}", config.boundary.name())?;
+            writeln!(out, "Seed: {}", results.seed)?;
+            writeln!(out, "Recorded steps: {} ({} warm-up steps per run)", results.steps, model.warmup)?;
+            writeln!(out, "Mean tree density: {:.4}", results.mean_density)?;
+            writeln!(out, "Fires: {}", results.fires())?;
+            match results.mean_size() {
+                Some(mean) => writeln!(out, "Mean fire size: {:.2} trees, largest {}", mean, results.max_size())?,
+                None => writeln!(out, "Mean fire size: no fires")?,
+            }
+            writeln!(out, "Power-law exponent (fit): {:.3}", results.tau)?;
+            writeln!(out, "Fire sizes:")?;
+            for bin in &results.histogram {
+                writeln!(out, "  {:>8} - {:<8} {:>8}  n(s) = {:.3e}", bin.min, bin.max, bin.count, bin.frequency)?;
+            }
+            writeln!(out, "Elapsed time: {:.2?}", elapsed)
+        }
+        OutputFormat::Json => {
+            let histogram: Vec<String> = results
+                .histogram
+                .iter()
+                .map(|bin| {
+                    format!(
+                        "    {{\"size_min\": {}, \"size_max\": {}, \"fires\": {}, \"frequency\": {}}}",
+                        bin.min, bin.max, bin.count, json_number(bin.frequency)
+                    )
+                })
+                .collect();
+            writeln!(out, "{{")?;
+            writeln!(out, "  \"config\": {},", json_config(config, results.seed))?;
+            writeln!(
+                out,
+                "  \"model\": {{\"name\": \"drossel-schwabl\", \"growth\": {}, \"lightning\": {}, \"steps\": {}, \"warmup\": {}}},",
+                model.growth, model.lightning, model.steps, model.warmup
+            )?;
+            writeln!(out, "  \"recorded_steps\": {},", results.steps)?;
+            writeln!(out, "  \"mean_density\": {},", json_number(results.mean_density))?;
+            writeln!(out, "  \"fires\": {},", results.fires())?;
+            writeln!(out, "  \"mean_size\": {},", results.mean_size().map_or("null".into(), json_number))?;
+            writeln!(out, "  \"max_size\": {},", results.max_size())?;
+            writeln!(out, "  \"tau\": {},", json_number(results.tau))?;
+            writeln!(out, "  \"histogram\": [")?;
+            writeln!(out, "{}", histogram.join(",\n"))?;
+            writeln!(out, "  ],")?;
+            writeln!(out, "  \"elapsed_seconds\": {}", elapsed.as_secs_f64())?;
+            writeln!(out, "}}")
+        }
+        OutputFormat::Csv => {
+            writeln!(out, "{}", CSV_FIRE_SIZES_HEADER)?;
+            for bin in &results.histogram {
+                writeln!(out, "{},{},{},{}", bin.min, bin.max, bin.count, bin.frequency)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Writes every season of a multi-season batch in the configured format.
+///
+/// Text is a summary and one line per season (or the mean burned percentage alone with `--quiet`),
+/// JSON one object with the config echo, regime and seasons, CSV one row per season.
+pub fn write_seasons(
+    out: &mut dyn Write,
+    config: &Config,
+    model: Seasons,
+    results: &SeasonResults,
+    elapsed: Duration,
+) -> io::Result<()> {
+    let recovery = match model.recovery {
+        Recovery::Logistic { rate } => format!("logistic, rate {}", rate),
+        Recovery::Age { maturity } => format!("age, maturity {} years", maturity),
+    };
+    match config.output_format {
+        OutputFormat::Text if config.quiet => {
+            writeln!(out, "{:.5}", results.mean_burned_percent())
+        }
+        OutputFormat::Text => {
+            writeln!(out, "Multi-season Results:")?;
+            writeln!(out, "---------------------")?;
+            writeln!(out, "Grid size: {}x{}", config.width, config.height)?;
+            writeln!(out, "Tree density: {:.2}", config.density)?;
+            writeln!(out, "Seasons: {} with {} recovery years each", model.seasons, model.years)?;
+            writeln!(out, "Recovery: {}", recovery)?;
+            writeln!(out, "Burn pattern: {:?}", config.burn_pattern)?;
+            writeln!(out, "Seed: {}", results.seed)?;
+            writeln!(out, "Mean burned per season: {:.2}%", results.mean_burned_percent())?;
+            writeln!(out, "Mean cover after recovery: {:.3}", results.mean_cover())?;
+            writeln!(
+                out,
+                "{:>4} {:>6} {:>9} {:>8} {:>12} {:>11} {:>8}  trees aged {}",
+                "run", "season", "burned", "burned%", "cover before", "cover after", "mean age",
+                age_class_labels().join(" / ")
+            )?;
+            for season in &results.seasons {
+                writeln!(
+                    out,
+                    "{:>4} {:>6} {:>9} {:>7.2}% {:>12.3} {:>11.3} {:>8.1}  {}",
+                    season.run, season.season + 1, season.burned, season.burned_percent(),
+                    season.cover_before, season.cover_after, season.mean_age,
+                    season.age_classes.map(|n| n.to_string()).join(" / ")
+                )?;
+            }
+            writeln!(out, "Elapsed time: {:.2?}", elapsed)
+        }
+        OutputFormat::Json => {
+            let labels = age_class_labels();
+            let seasons: Vec<String> = results
+                .seasons
+                .iter()
+                .map(|season| {
+                    let ages: Vec<String> = labels
+                        .iter()
+                        .zip(season.age_classes)
+                        .map(|(label, trees)| format!("\"{}\": {}", label, trees))
+                        .collect();
+                    format!(
+                        "    {{\"run\": {}, \"season\": {}, \"trees_before\": {}, \"burned\": {}, \"burned_percent\": {}, \"cover_before\": {}, \"cover_after\": {}, \"mean_age\": {}, \"age_classes\": {{{}}}}}",
+                        season.run, season.season + 1, season.trees_before, season.burned,
+                        json_number(season.burned_percent()), season.cover_before, season.cover_after,
+                        json_number(season.mean_age), ages.join(", ")
+                    )
+                })
+                .collect();
+            let recovery = match model.recovery {
+                Recovery::Logistic { rate } => format!("\"rate\": {}", rate),
+                Recovery::Age { maturity } => format!("\"maturity\": {}", maturity),
+            };
+            writeln!(out, "{{")?;
+            writeln!(out, "  \"config\": {},", json_config(config, results.seed))?;
+            writeln!(
+                out,
+                "  \"model\": {{\"name\": \"seasons\", \"seasons\": {}, \"years\": {}, \"recovery\": {{\"name\": \"{}\", {}}}}},",
+                model.seasons, model.years, model.recovery.name(), recovery
+            )?;
+            writeln!(out, "  \"mean_burned_percent\": {},", json_number(results.mean_burned_percent()))?;
+            writeln!(out, "  \"mean_cover\": {},", json_number(results.mean_cover()))?;
+            writeln!(out, "  \"seasons\": [")?;
+            writeln!(out, "{}", seasons.join(",\n"))?;
+            writeln!(out, "  ],")?;
+            writeln!(out, "  \"elapsed_seconds\": {}", elapsed.as_secs_f64())?;
+            writeln!(out, "}}")
+        }
+        OutputFormat::Csv => {
+            writeln!(out, "{}", CSV_SEASONS_HEADER)?;
+            for season in &results.seasons {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{}",
+                    season.run, season.season + 1, season.trees_before, season.burned, season.burned_percent(),
+                    season.cover_before, season.cover_after, season.mean_age,
+                    season.age_classes.map(|n| n.to_string()).join(",")
+                )?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Writes the critical density found by a threshold search and every density it tried.
+pub fn write_threshold(
+    out: &mut dyn Write,
+    config: &Config,
+    model: Threshold,
+    results: &ThresholdResults,
+    elapsed: Duration,
+) -> io::Result<()> {
+    let criterion = match model.criterion {
+        Criterion::Percolation => "percolation probability",
+        Criterion::Spanning => "spanning probability",
+        Criterion::Burned => "mean burned fraction",
+    };
+    match config.output_format {
+        OutputFormat::Text if config.quiet => {
+            writeln!(out, "{:.5}", results.estimate)
+        }
+        OutputFormat::Text => {
+            writeln!(out, "Critical Density Search:")?;
+            writeln!(out, "------------------------")?;
+            writeln!(out, "Grid size: {}x{}", config.width, config.height)?;
+            writeln!(out, "Burn pattern: {:?}", config.burn_pattern)?;
+            writeln!(out, "Criterion: {} reaching {}", criterion, model.target)?;
+            writeln!(out, "Runs per density: {}", config.simulations)?;
+            writeln!(out, "Seed: {}", results.seed)?;
+            writeln!(out, "Critical density: {:.4} ± {:.4} (standard error)", results.estimate, results.std_error)?;
+            writeln!(out, "Final bracket: {:.4} to {:.4}", results.low, results.high)?;
+            writeln!(out, "Slope at the threshold: {:.2} per unit of density", results.slope)?;
+            writeln!(out, "{:>9} {:>8} {:>10}", "density", "value", "std error")?;
+            for point in &results.points {
+                writeln!(out, "{:>9.5} {:>8.4} {:>10.4}", point.density, point.value, point.std_error)?;
+            }
+            writeln!(out, "Elapsed time: {:.2?}", elapsed)
+        }
+        OutputFormat::Json => {
+            let points: Vec<String> = results
+                .points
+                .iter()
+                .map(|point| {
+                    format!(
+                        "    {{\"density\": {}, \"value\": {}, \"std_error\": {}}}",
+                        point.density, json_number(point.value), json_number(point.std_error)
+                    )
+                })
+                .collect();
+            writeln!(out, "{{")?;
+            writeln!(out, "  \"config\": {},", json_config(config, results.seed))?;
+            writeln!(
+                out,
+                "  \"model\": {{\"name\": \"threshold\", \"criterion\": \"{}\", \"target\": {}, \"tolerance\": {}}},",
+                model.criterion.name(), model.target, model.tolerance
+            )?;
+            writeln!(out, "  \"estimate\": {},", json_number(results.estimate))?;
+            writeln!(out, "  \"std_error\": {},", json_number(results.std_error))?;
+            writeln!(out, "  \"bracket\": [{}, {}],", results.low, results.high)?;
+            writeln!(out, "  \"slope\": {},", json_number(results.slope))?;
+            writeln!(out, "  \"points\": [")?;
+            writeln!(out, "{}", points.join(",\n"))?;
+            writeln!(out, "  ],")?;
+            writeln!(out, "  \"elapsed_seconds\": {}", elapsed.as_secs_f64())?;
+            writeln!(out, "}}")
+        }
+        OutputFormat::Csv => {
+            writeln!(out, "{}", CSV_THRESHOLD_HEADER)?;
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{},{}",
+                model.criterion.name(), model.target, model.tolerance, results.estimate, results.std_error,
+                results.low, results.high, results.slope, config.simulations, results.seed, elapsed.as_secs_f64()
+            )?;
+            writeln!(out)?;
+            writeln!(out, "{}", CSV_THRESHOLD_POINTS_HEADER)?;
+            for point in &results.points {
+                writeln!(out, "{},{},{}", point.density, point.value, point.std_error)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Writes a finite-size scaling study: the table of every size and density, the percolation
+/// curve fit of every size and the extrapolated parameters.
+pub fn write_scaling(out: &mut dyn Write, config: &Config, results: &ScalingResults, elapsed: Duration) -> io::Result<()> {
+    match config.output_format {
+        OutputFormat::Text if config.quiet => {
+            writeln!(out, "{:.5} {:.5}", results.critical_density.value, results.nu.value)
+        }
+        OutputFormat::Text => {
+            writeln!(out, "Finite-Size Scaling Study:")?;
+            writeln!(out, "--------------------------")?;
+            writeln!(out, "Burn pattern: {:?}", config.burn_pattern)?;
+            writeln!(out, "Runs per point: {}", config.simulations)?;
+            writeln!(out, "Seed: {}", results.seed)?;
+            writeln!(
+                out,
+                "{:>6} {:>9} {:>11} {:>17} {:>9} {:>9} {:>9}",
+                "size", "density", "percolation", "95% CI", "spanning", "burned", "largest"
+            )?;
+            for point in &results.points {
+                let (low, high) = point.percolation.interval();
+                writeln!(
+                    out,
+                    "{:>6} {:>9.4} {:>11.4} {:>7.4} - {:<7.4} {:>9.4} {:>8.2}% {:>8.2}%",
+                    point.size, point.density, point.percolation.estimate(), low, high,
+                    point.spanning.estimate(), point.mean_burned, point.mean_largest_cluster
+                )?;
+            }
+            writeln!(out, "{:>6} {:>9} {:>9} {:>9}", "size", "P = 0.5", "mean", "width")?;
+            for fit in &results.fits {
+                writeln!(out, "{:>6} {:>9.4} {:>9.4} {:>9.4}", fit.size, fit.crossing, fit.mean, fit.width)?;
+            }
+            writeln!(out, "Critical density (L → ∞): {}", text_estimate(results.critical_density, 4))?;
+            writeln!(out, "Correlation-length exponent ν: {}", text_estimate(results.nu, 3))?;
+            writeln!(out, "Elapsed time: {:.2?}", elapsed)
+        }
+        OutputFormat::Json => {
+            let points: Vec<String> = results
+                .points
+                .iter()
+                .map(|point| {
+                    format!(
+                        "    {{\"size\": {}, \"density\": {}, \"percolation\": {}, \"spanning\": {}, \"mean_burned\": {}, \"mean_largest_cluster\": {}, \"runs\": {}}}",
+                        point.size,
+                        point.density,
+                        json_proportion(point.percolation),
+                        json_proportion(point.spanning),
+                        json_number(point.mean_burned),
+                        json_number(point.mean_largest_cluster),
+                        point.percolation.runs
+                    )
+                })
+                .collect();
+            let fits: Vec<String> = results
+                .fits
+                .iter()
+                .map(|fit| {
+                    format!(
+                        "    {{\"size\": {}, \"crossing\": {}, \"mean\": {}, \"width\": {}}}",
+                        fit.size, json_number(fit.crossing), json_number(fit.mean), json_number(fit.width)
+                    )
+                })
+                .collect();
+            writeln!(out, "{{")?;
+            writeln!(out, "  \"config\": {},", json_config(config, results.seed))?;
+            writeln!(out, "  \"critical_density\": {},", json_estimate(results.critical_density))?;
+            writeln!(out, "  \"nu\": {},", json_estimate(results.nu))?;
+            writeln!(out, "  \"sizes\": [")?;
+            writeln!(out, "{}", fits.join(",\n"))?;
+            writeln!(out, "  ],")?;
+            writeln!(out, "  \"points\": [")?;
+            writeln!(out, "{}", points.join(",\n"))?;
+            writeln!(out, "  ],")?;
+            writeln!(out, "  \"elapsed_seconds\": {}", elapsed.as_secs_f64())?;
+            writeln!(out, "}}")
+        }
+        OutputFormat::Csv => {
+            writeln!(out, "{}", CSV_SCALING_POINTS_HEADER)?;
+            for point in &results.points {
+                let (low, high) = point.percolation.interval();
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{}",
+                    point.size, point.density, point.percolation.estimate(), low, high,
+                    point.spanning.estimate(), point.mean_burned, point.mean_largest_cluster, point.percolation.runs
+                )?;
+            }
+            writeln!(out)?;
+            writeln!(out, "{}", CSV_SCALING_SIZES_HEADER)?;
+            for fit in &results.fits {
+                writeln!(out, "{},{},{},{}", fit.size, fit.crossing, fit.mean, fit.width)?;
+            }
+            writeln!(out)?;
+            writeln!(out, "{}", CSV_SCALING_FIT_HEADER)?;
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{}",
+                results.critical_density.value, results.critical_density.std_error, results.nu.value,
+                results.nu.std_error, config.simulations, results.seed, elapsed.as_secs_f64()
+            )
+        }
+    }
+}
+
+/// A fitted value with its standard error, when there is one.
+fn text_estimate(estimate: Estimate, decimals: usize) -> String {
+    if estimate.std_error.is_finite() {
+        format!("{:.*} ± {:.*} (standard error)", decimals, estimate.value, decimals, estimate.std_error)
+    } else {
+        format!("{:.*}", decimals, estimate.value)
+    }
+}
+
+/// Streams the points of a sweep, one row per combination of the swept values.
+pub struct SweepWriter<'a> {
+    out: &'a mut dyn Write,
+    format: OutputFormat,
+    axes: Vec<Axis>, // named in the text rows
+    rows: usize,
+}
+
+impl<'a> SweepWriter<'a> {
+    /// Writes the header; `config` is the base config of the sweep.
+    pub fn new(out: &'a mut dyn Write, config: &Config, sweep: &Sweep) -> io::Result<Self> {
+        match config.output_format {
+            OutputFormat::Text => {}
+            OutputFormat::Json => {
+                writeln!(out, "{{")?;
+                let swept: Vec<String> = sweep.axes.iter().map(|axis| format!("\"{}\"", axis.name())).collect();
+                writeln!(out, "  \"config\": {},", json_config(config, config.seed.unwrap_or_default()))?;
+                writeln!(out, "  \"swept\": [{}],", swept.join(", "))?;
+                writeln!(out, "  \"points\": [")?;
+            }
+            OutputFormat::Csv => writeln!(out, "{}", CSV_SUMMARY_HEADER)?,
+        }
+        Ok(SweepWriter { out, format: config.output_format, axes: sweep.axes.clone(), rows: 0 })
+    }
+
+    /// Writes one sweep point; `config` is the config the point was run with.
+    pub fn row(&mut self, config: &Config, results: &SimulationResults, elapsed: Duration) -> io::Result<()> {
+        match self.format {
+            OutputFormat::Text => {
+                let values: Vec<String> = self.axes.iter().map(|axis| axis.describe(config)).collect();
+                writeln!(
+                    self.out,
+                    "{}, average burned: {:.5} across {} simulations",
+                    values.join(", "), results.average_burned, config.simulations
+                )?
+            }
+            OutputFormat::Json => {
+                if self.rows > 0 {
+                    writeln!(self.out, ",")?;
+                }
+                write!(
+                    self.out,
+                    "    {{\"density\": {}, \"spread_prob\": {}, \"width\": {}, \"height\": {}, \"burn_pattern\": \"{}\", \"simulations\": {}, \"min_burned\": {}, \"max_burned\": {}, \"average_burned\": {}, \"statistics\": {}, \"elapsed_seconds\": {}}}",
+                    config.density,
+                    config.spread_prob,
+                    config.width,
+                    config.height,
+                    config.burn_pattern.name(),
+                    config.simulations,
+                    json_number(results.min_burned),
+                    json_number(results.max_burned),
+                    json_number(results.average_burned),
+                    json_statistics(results),
+                    elapsed.as_secs_f64()
+                )?;
+            }
+            OutputFormat::Csv => writeln!(self.out, "{}", csv_summary_row(config, results, elapsed))?,
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Closes the document; `elapsed` is the time of the whole sweep.
+    pub fn finish(self, elapsed: Duration) -> io::Result<()> {
+        match self.format {
+            OutputFormat::Text => writeln!(self.out, "Elapsed time: {:.2?}", elapsed),
+            OutputFormat::Json => {
+                if self.rows > 0 {
+                    writeln!(self.out)?;
+                }
+                writeln!(self.out, "  ],")?;
+                writeln!(self.out, "  \"elapsed_seconds\": {}", elapsed.as_secs_f64())?;
+                writeln!(self.out, "}}")
+            }
+            OutputFormat::Csv => Ok(()),
+        }
+    }
+}
+
+/// Placement name with its parameters, e.g. "hurst (H = 0.7)".
+fn placement_details(placement: Placement) -> String {
+    match placement {
+        Placement::Uniform => "uniform".into(),
+        Placement::Noise { scale, octaves } => format!("noise (scale {}, {} octaves)", scale, octaves),
+        Placement::Poisson { parents, radius } => format!("poisson ({} parents per cell, radius {})", parents, radius),
+        Placement::Hurst { hurst } => format!("hurst (H = {})", hurst),
+    }
+}
+
+fn json_config(config: &Config, seed: u64) -> String {
+    let wind = match config.burn_pattern {
+        BurnPattern::Wind(wind) => format!(
+            ", \"wind\": {{\"direction\": {}, \"strength\": {}, \"reach\": {}}}",
+            wind.direction, wind.strength, wind.reach
+        ),
+        _ => String::new(),
+    };
+    let terrain = match config.terrain {
+        TerrainSource::Flat => String::new(),
+        TerrainSource::DiamondSquare { roughness } => format!(
+            ", \"terrain\": {{\"source\": \"diamond-square\", \"roughness\": {}, \"relief\": {}, \"slope_factor\": {}}}",
+            roughness, config.relief, config.slope_factor
+        ),
+        TerrainSource::Heightmap(_) => format!(
+            ", \"terrain\": {{\"source\": \"heightmap\", \"relief\": {}, \"slope_factor\": {}}}",
+            config.relief, config.slope_factor
+        ),
+    };
+    let placement = match config.placement {
+        _ if config.map.is_some() => format!(", \"map\": {}", json_string(&config.map.as_ref().unwrap().source)),
+        Placement::Uniform => String::new(),
+        Placement::Noise { scale, octaves } => format!(
+            ", \"placement\": {{\"name\": \"noise\", \"scale\": {}, \"octaves\": {}}}",
+            scale, octaves
+        ),
+        Placement::Poisson { parents, radius } => format!(
+            ", \"placement\": {{\"name\": \"poisson\", \"parents\": {}, \"radius\": {}}}",
+            parents, radius
+        ),
+        Placement::Hurst { hurst } => format!(", \"placement\": {{\"name\": \"hurst\", \"hurst\": {}}}", hurst),
+    };
+    let vegetation: Vec<String> = config
+        .vegetation
+        .densities(config.density)
+        .into_iter()
+        .map(|(class, density)| {
+            let fuel = config.classes[class].fuel;
+            if class.is_fuel() {
+                format!(
+                    "\"{}\": {{\"density\": {}, \"ignition\": {}, \"burn_steps\": {}, \"spread_phase\": [{}, {}]}}",
+                    class.name(), density, config.classes[class].ignition,
+                    fuel.burn_steps, fuel.spread_from + 1, fuel.spread_until.min(fuel.burn_steps)
+                )
+            } else {
+                format!("\"{}\": {{\"density\": {}}}", class.name(), density)
+            }
+        })
+        .collect();
+    format!(
+        "{{\"width\": {}, \"height\": {}, \"density\": {}, \"spread_prob\": {}, \"vegetation\": {{{}}}{}, \"simulations\": {}, \"burn_pattern\": \"{}\"{}, \"boundary\": \"{}\"{}, \"seed\": {}, \"threads\": {}}}",
+        config.width, config.height, config.density, config.spread_prob,
+        vegetation.join(", "), placement,
+        config.simulations, config.burn_pattern.name(),
+        wind, config.boundary.name(), terrain, seed, config.threads
+    )
+}
+
+fn json_statistics(results: &SimulationResults) -> String {
+    let stats = &results.stats;
+    let by_class: Vec<String> = fuel_classes()
+        .filter(|&class| stats.class_trees[class as usize] > 0)
+        .map(|class| {
+            format!(
+                "\"{}\": {{\"trees\": {}, \"burned\": {}, \"burned_percent\": {}}}",
+                class.name(),
+                stats.class_trees[class as usize],
+                stats.class_burned[class as usize],
+                json_number(stats.class_burned_percent(class))
+            )
+        })
+        .collect();
+    format!(
+        "{{\"runs\": {}, \"skipped\": {}, \"mean\": {}, \"std_dev\": {}, \"median\": {}, \"p05\": {}, \"p25\": {}, \"p75\": {}, \"p95\": {}, \"ci95\": {}, \"mean_steps\": {}, \"max_steps\": {}, \"mean_peak_front\": {}, \"max_peak_front\": {}, \"mean_spread_rate\": {}, \"mean_clusters\": {}, \"mean_largest_cluster\": {}, \"mean_cluster_size\": {}, \"spanning\": {{\"either\": {}, \"left_right\": {}, \"top_bottom\": {}, \"both\": {}, \"wrapping\": {}}}, \"percolation\": {}, \"by_class\": {{{}}}}}",
+        stats.runs,
+        stats.skipped,
+        json_number(stats.mean),
+        json_number(stats.std_dev),
+        json_number(stats.median),
+        json_number(stats.p05),
+        json_number(stats.p25),
+        json_number(stats.p75),
+        json_number(stats.p95),
+        json_number(stats.ci95),
+        json_number(stats.mean_steps),
+        stats.max_steps,
+        json_number(stats.mean_peak_front),
+        stats.max_peak_front,
+        json_number(stats.mean_spread_rate),
+        json_number(stats.mean_clusters),
+        json_number(stats.mean_largest_cluster),
+        json_number(stats.mean_cluster_size),
+        json_proportion(stats.spanning),
+        json_proportion(stats.spanning_left_right),
+        json_proportion(stats.spanning_top_bottom),
+        json_proportion(stats.spanning_both),
+        json_proportion(stats.wrapping),
+        json_proportion(stats.percolation),
+        by_class.join(", ")
+    )
+}
+
+fn csv_summary_row(config: &Config, results: &SimulationResults, elapsed: Duration) -> String {
+    let stats = &results.stats;
+    format!(
+        "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+        config.width,
+        config.height,
+        config.density,
+        config.spread_prob,
+        config
+            .vegetation
+            .densities(config.density)
+            .iter()
+            .map(|(class, density)| format!("{}:{}", class.name(), density))
+            .collect::<Vec<_>>()
+            .join(" "),
+        config.placement.name(),
+        config.burn_pattern.name(),
+        config.boundary.name(),
+        config.terrain.name(),
+        config.simulations,
+        results.seed,
+        results.min_burned,
+        results.max_burned,
+        results.average_burned,
+        stats.runs,
+        stats.skipped,
+        stats.mean,
+        stats.std_dev,
+        stats.median,
+        stats.p05,
+        stats.p25,
+        stats.p75,
+        stats.p95,
+        stats.ci95,
+        stats.mean_steps,
+        stats.max_steps,
+        stats.mean_peak_front,
+        stats.max_peak_front,
+        stats.mean_spread_rate,
+        stats.mean_clusters,
+        stats.mean_largest_cluster,
+        stats.mean_cluster_size,
+        stats.spanning.estimate(),
+        stats.spanning.interval().0,
+        stats.spanning.interval().1,
+        stats.spanning_left_right.estimate(),
+        stats.spanning_top_bottom.estimate(),
+        stats.spanning_both.estimate(),
+        stats.wrapping.estimate(),
+        stats.percolation.estimate(),
+        stats.class_burned_percent(Vegetation::Grass),
+        stats.class_burned_percent(Vegetation::Shrub),
+        stats.class_burned_percent(Vegetation::Conifer),
+        stats.class_burned_percent(Vegetation::Deciduous),
+        elapsed.as_secs_f64()
+    )
+}
+
+fn fuel_classes() -> impl Iterator<Item = Vegetation> {
+    Vegetation::ALL.into_iter().filter(|class| class.is_fuel())
+}
+
+/// A JSON string literal, quotes, backslashes and control characters escaped.
+fn json_string(value: &str) -> String {
+    let mut literal = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => literal.extend(['\\', c]),
+            c if c.is_control() => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// A share of the runs as `{"probability": p, "ci95": [low, high]}`.
+fn json_proportion(proportion: Proportion) -> String {
+    let (low, high) = proportion.interval();
+    format!(
+        "{{\"probability\": {}, \"ci95\": [{}, {}]}}",
+        json_number(proportion.estimate()),
+        json_number(low),
+        json_number(high)
+    )
+}
+
+/// A fitted parameter as `{"value": v, "std_error": e}`.
+fn json_estimate(estimate: Estimate) -> String {
+    format!("{{\"value\": {}, \"std_error\": {}}}", json_number(estimate.value), json_number(estimate.std_error))
+}
+
+/// JSON has no NaN or infinity, batches without trees report `null`.
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::run_simulations;
+
+    fn seeded_config(output_format: OutputFormat) -> Config {
+        Config {
+            width: 10,
+            height: 10,
+            density: 0.5,
+            simulations: 3,
+            graphics: false,
+            seed: Some(11),
+            output_format,
+            record_runs: true,
+            ..Config::default()
+        }
+    }
+
+    fn render(config: &Config) -> String {
+        let results = run_simulations(config);
+        let mut out = Vec::new();
+        write_results(&mut out, config, &results, Duration::from_millis(5)).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_csv_has_summary_and_runs() {
+        let text = render(&seeded_config(OutputFormat::Csv));
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], CSV_SUMMARY_HEADER);
+        assert!(lines[1].starts_with("10,10,0.5,1,conifer:0.5,uniform,moore,open,flat,3,11,"));
+        assert_eq!(lines[2], "");
+        assert_eq!(lines[3], CSV_RUNS_HEADER);
+        assert_eq!(lines.len(), 4 + 3);
+    }
+
+    #[test]
+    fn test_json_lists_every_run() {
+        let text = render(&seeded_config(OutputFormat::Json));
+
+        assert!(text.contains("\"burn_pattern\": \"moore\""));
+        assert!(text.contains("\"seed\": 11"));
+        assert!(text.contains("\"statistics\": {\"runs\": 3, \"skipped\": 0, "));
+        assert_eq!(text.matches("{\"index\": ").count(), 3);
+    }
+
+    #[test]
+    fn test_runs_left_out_unless_recorded() {
+        let config = Config { record_runs: false, ..seeded_config(OutputFormat::Csv) };
+        assert_eq!(render(&config).lines().count(), 2);
+
+        let json = render(&Config { output_format: OutputFormat::Json, ..config });
+        assert!(!json.contains("\"runs\": ["));
+        assert!(!json.contains("\"terrain\""));
+        assert!(json.trim_end().ends_with("}"));
+    }
+
+    #[test]
+    fn test_json_sweep_separates_points() {
+        let config = seeded_config(OutputFormat::Json);
+        let results = run_simulations(&config);
+        let mut out = Vec::new();
+
+        let mut sweep = SweepWriter::new(&mut out, &config, &Sweep::default()).unwrap();
+        sweep.row(&config, &results, Duration::ZERO).unwrap();
+        sweep.row(&Config { density: 0.6, ..config.clone() }, &results, Duration::ZERO).unwrap();
+        sweep.finish(Duration::ZERO).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.matches("\"density\": 0.6, ").count(), 1);
+        assert!(text.contains("},\n    {\"density\": 0.6, \"spread_prob\": 1, "));
+        assert!(text.contains("}\n  ],\n"));
+        assert!(text.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_sweep_rows_name_the_swept_values() {
+        let mut sweep = Sweep::default();
+        sweep.push(Axis::Size(vec![(8, 8), (16, 4)]));
+        sweep.push(Axis::Simulations(vec![2]));
+        let config = seeded_config(OutputFormat::Text);
+        let render = |config: &Config| {
+            let mut out = Vec::new();
+            let mut writer = SweepWriter::new(&mut out, config, &sweep).unwrap();
+            for point in sweep.combinations(config) {
+                writer.row(&point, &run_simulations(&point), Duration::ZERO).unwrap();
+            }
+            writer.finish(Duration::ZERO).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let text = render(&config);
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("size: 8x8, simulations: 2, average burned: "));
+        assert!(lines[1].starts_with("size: 16x4, simulations: 2, "));
+
+        let json = render(&Config { output_format: OutputFormat::Json, ..config.clone() });
+        assert!(json.contains("\"swept\": [\"size\", \"simulations\"],"));
+        assert!(json.contains("\"width\": 16, \"height\": 4, \"burn_pattern\": \"moore\", \"simulations\": 2, "));
+
+        let csv = render(&Config { output_format: OutputFormat::Csv, ..config });
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.lines().nth(2).unwrap().starts_with("16,4,"));
+    }
+
+    #[test]
+    fn test_terrain_is_echoed() {
+        let config = Config {
+            terrain: TerrainSource::DiamondSquare { roughness: 0.5 },
+            ..seeded_config(OutputFormat::Json)
+        };
+        assert!(render(&config).contains(
+            "\"terrain\": {\"source\": \"diamond-square\", \"roughness\": 0.5, \"relief\": 10, \"slope_factor\": 4.5}"
+        ));
+
+        let csv = render(&Config { output_format: OutputFormat::Csv, ..config });
+        assert!(csv.lines().nth(1).unwrap().starts_with("10,10,0.5,1,conifer:0.5,uniform,moore,open,diamond-square,3,11,"));
+    }
+
+    #[test]
+    fn test_map_is_echoed() {
+        let mut map = crate::map::ForestMap::parse_ascii("TT.\n*G~\n").unwrap();
+        map.source = "maps\\stand \"7\".txt".into();
+        let config = Config {
+            width: 3,
+            height: 2,
+            map: Some(std::sync::Arc::new(map)),
+            ..seeded_config(OutputFormat::Json)
+        };
+        let json = render(&config);
+        assert!(json.contains(", \"map\": \"maps\\\\stand \\\"7\\\".txt\", "));
+        // the mapped fire starts from its burning cell in every run
+        assert_eq!(json.matches("\"ignition\": [0, 1]").count(), 3);
+        let text = render(&Config { output_format: OutputFormat::Text, ..config });
+        assert!(text.contains("Map: maps\\stand \"7\".txt\n"));
+    }
+
+    #[test]
+    fn test_placement_is_echoed() {
+        let config = Config { placement: Placement::Hurst { hurst: 0.4 }, ..seeded_config(OutputFormat::Json) };
+        assert!(render(&config).contains("}}, \"placement\": {\"name\": \"hurst\", \"hurst\": 0.4}, \"simulations\": 3"));
+        assert!(!render(&seeded_config(OutputFormat::Json)).contains("\"placement\""));
+
+        let text = render(&Config { output_format: OutputFormat::Text, ..config.clone() });
+        assert!(text.contains("Placement: hurst (H = 0.4)\n"));
+        let csv = render(&Config { output_format: OutputFormat::Csv, ..config });
+        assert!(csv.lines().nth(1).unwrap().starts_with("10,10,0.5,1,conifer:0.5,hurst,moore,"));
+    }
+
+    #[test]
+    fn test_burned_area_by_class() {
+        let (vegetation, density) = crate::vegetation::VegetationMix::parse("grass=0.3,shrub=0.2,rock=0.1").unwrap();
+        let config = Config { vegetation, density, ..seeded_config(OutputFormat::Json) };
+
+        let json = render(&config);
+        assert!(json.contains("\"vegetation\": {\"grass\": {\"density\": 0.3, \"ignition\": 1, \"burn_steps\": 1, \"spread_phase\": [1, 1]}, "));
+        assert!(json.contains("\"rock\": {\"density\": 0.1}}"));
+        assert!(json.contains("\"by_class\": {\"grass\": {\"trees\": 90, "));
+        assert!(!json.contains("\"conifer\": {\"trees\""));
+
+        let text = render(&Config { output_format: OutputFormat::Text, ..config.clone() });
+        assert!(text.contains("Vegetation: shrub 0.20 (ignition 0.80, burns 2 steps, spreading in steps 1-2)"));
+        assert!(text.contains("Burned by class: grass "));
+
+        let csv = render(&Config { output_format: OutputFormat::Csv, ..config });
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.starts_with("10,10,0.5,1,grass:0.3 shrub:0.2 rock:0.1,uniform,moore,"));
+        assert_eq!(row.split(',').count(), CSV_SUMMARY_HEADER.split(',').count());
+        assert_eq!(row.split(',').rev().nth(2), Some("NaN")); // no conifers
+    }
+
+    #[test]
+    fn test_fire_size_distribution() {
+        let model = DrosselSchwabl { growth: 0.05, lightning: 0.001, steps: 100, warmup: 50 };
+        let config = Config { width: 32, height: 32, ..seeded_config(OutputFormat::Csv) };
+        let results = crate::drossel_schwabl::run_drossel_schwabl(&config, model);
+        let render = |config: &Config| {
+            let mut out = Vec::new();
+            write_fire_sizes(&mut out, config, model, &results, Duration::ZERO).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let csv = render(&config);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_FIRE_SIZES_HEADER);
+        assert_eq!(lines.len(), 1 + results.histogram.len());
+        assert!(lines[1].starts_with("1,1,"));
+
+        let json = render(&Config { output_format: OutputFormat::Json, ..config.clone() });
+        assert!(json.contains("\"model\": {\"name\": \"drossel-schwabl\", \"growth\": 0.05, "));
+        assert!(json.contains(&format!("\"fires\": {},", results.fires())));
+        assert!(json.contains("\"histogram\": [\n    {\"size_min\": 1, \"size_max\": 1, "));
+
+        let text = render(&Config { output_format: OutputFormat::Text, ..config.clone() });
+        assert!(text.contains("Power-law exponent (fit): "));
+
+        // without lightning there is no mean fire size to report
+        let calm = DrosselSchwabl { lightning: 0.0, ..model };
+        let results = crate::drossel_schwabl::run_drossel_schwabl(&config, calm);
+        assert_eq!(results.mean_size(), None);
+        let mut out = Vec::new();
+        write_fire_sizes(&mut out, &Config { output_format: OutputFormat::Json, ..config.clone() }, calm, &results, Duration::ZERO).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("\"mean_size\": null,"));
+        let mut out = Vec::new();
+        write_fire_sizes(&mut out, &Config { output_format: OutputFormat::Text, ..config }, calm, &results, Duration::ZERO).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("Mean fire size: no fires\n"));
+    }
+
+    #[test]
+    fn test_season_table() {
+        let model = Seasons { seasons: 4, years: 3, recovery: Recovery::Age { maturity: 5 } };
+        let config = Config { width: 20, height: 20, density: 0.7, simulations: 2, ..seeded_config(OutputFormat::Csv) };
+        let results = crate::seasons::run_seasons(&config, model);
+        let render = |config: &Config| {
+            let mut out = Vec::new();
+            write_seasons(&mut out, config, model, &results, Duration::ZERO).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let csv = render(&config);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_SEASONS_HEADER);
+        assert_eq!(lines.len(), 1 + 8);
+        assert!(lines[1].starts_with("0,1,280,"));
+        assert!(lines[8].starts_with("1,4,"));
+        assert_eq!(lines[1].split(',').count(), CSV_SEASONS_HEADER.split(',').count());
+
+        let json = render(&Config { output_format: OutputFormat::Json, ..config.clone() });
+        assert!(json.contains("\"recovery\": {\"name\": \"age\", \"maturity\": 5}"));
+        assert!(json.contains("\"age_classes\": {\"0-9\": "));
+        assert_eq!(json.matches("\"season\": ").count(), 8);
+
+        let text = render(&Config { output_format: OutputFormat::Text, ..config });
+        assert!(text.contains("Recovery: age, maturity 5 years"));
+        assert!(text.contains("trees aged 0-9 / 10-19 / 20-49 / 50+"));
+    }
+
+    #[test]
+    fn test_threshold_report() {
+        let model = Threshold { criterion: Criterion::Burned, target: 0.5, tolerance: 0.25 };
+        let results = ThresholdResults {
+            seed: 9,
+            points: vec![
+                crate::threshold::ThresholdPoint { density: 0.5, value: 0.2, std_error: 0.05 },
+                crate::threshold::ThresholdPoint { density: 0.75, value: 0.9, std_error: 0.01 },
+            ],
+            low: 0.5,
+            high: 0.75,
+            estimate: 0.6,
+            std_error: 0.03,
+            slope: 2.8,
+        };
+        let render = |config: &Config| {
+            let mut out = Vec::new();
+            write_threshold(&mut out, config, model, &results, Duration::ZERO).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let config = seeded_config(OutputFormat::Csv);
+        let csv = render(&config);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_THRESHOLD_HEADER);
+        assert!(lines[1].starts_with("burned,0.5,0.25,0.6,0.03,0.5,0.75,2.8,"));
+        assert_eq!(lines[3], CSV_THRESHOLD_POINTS_HEADER);
+        assert_eq!(lines[5], "0.75,0.9,0.01");
+
+        let json = render(&Config { output_format: OutputFormat::Json, ..config.clone() });
+        assert!(json.contains("\"model\": {\"name\": \"threshold\", \"criterion\": \"burned\", \"target\": 0.5, \"tolerance\": 0.25}"));
+        assert!(json.contains("\"bracket\": [0.5, 0.75]"));
+
+        let text = render(&Config { output_format: OutputFormat::Text, ..config });
+        assert!(text.contains("Criterion: mean burned fraction reaching 0.5"));
+        assert!(text.contains("Critical density: 0.6000 ± 0.0300"));
+    }
+
+    #[test]
+    fn test_scaling_report() {
+        use crate::scaling::{ScalingPoint, SizeFit};
+        let point = |size, density, count| ScalingPoint {
+            size,
+            density,
+            percolation: Proportion { count, runs: 4 },
+            spanning: Proportion { count: count / 2, runs: 4 },
+            mean_burned: 50.0,
+            mean_largest_cluster: 25.0,
+        };
+        let results = ScalingResults {
+            seed: 5,
+            points: vec![point(8, 0.5, 1), point(8, 0.7, 4), point(16, 0.5, 0), point(16, 0.7, 4)],
+            fits: vec![
+                SizeFit { size: 8, crossing: 0.6, mean: 0.62, width: 0.1 },
+                SizeFit { size: 16, crossing: 0.6, mean: 0.61, width: 0.05 },
+            ],
+            critical_density: Estimate { value: 0.6, std_error: f64::NAN },
+            nu: Estimate { value: 1.0, std_error: f64::NAN },
+        };
+        let render = |config: &Config| {
+            let mut out = Vec::new();
+            write_scaling(&mut out, config, &results, Duration::ZERO).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let config = seeded_config(OutputFormat::Csv);
+        let csv = render(&config);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_SCALING_POINTS_HEADER);
+        assert!(lines[1].starts_with("8,0.5,0.25,"));
+        assert!(lines[4].ends_with(",0.5,50,25,4"));
+        assert_eq!(lines[6], CSV_SCALING_SIZES_HEADER);
+        assert_eq!(lines[8], "16,0.6,0.61,0.05");
+        assert_eq!(lines[10], CSV_SCALING_FIT_HEADER);
+        assert!(lines[11].starts_with("0.6,NaN,1,NaN,"));
+
+        let json = render(&Config { output_format: OutputFormat::Json, ..config.clone() });
+        assert!(json.contains("\"critical_density\": {\"value\": 0.6, \"std_error\": null}"));
+        assert!(json.contains("{\"size\": 16, \"crossing\": 0.6, \"mean\": 0.61, \"width\": 0.05}"));
+
+        let text = render(&Config { output_format: OutputFormat::Text, ..config });
+        assert!(text.contains("Critical density (L → ∞): 0.6000\n"));
+        assert!(text.contains("Correlation-length exponent ν: 1.000\n"));
+    }
+
+    #[test]
+    fn test_json_number_without_trees() {
+        assert_eq!(json_number(f64::NAN), "null");
+        assert_eq!(json_number(12.5), "12.5");
+    }
+}
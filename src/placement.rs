@@ -0,0 +1,198 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::terrain::Terrain;
+
+/// How the trees are spread over the grid. Every generator ranks the cells and
+/// plants the best-ranked ones, so the tree count is exact whatever the pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Placement {
+    /// Uniformly random cells (Fisher–Yates shuffle).
+    #[default]
+    Uniform,
+    /// Fractal value noise thresholded at the target density: patches about
+    /// `scale` cells across, with `octaves` finer layers of detail.
+    Noise { scale: f64, octaves: u32 },
+    /// Thomas cluster process: parent points with intensity `parents` per cell,
+    /// trees scattered around them with a Gaussian of deviation `radius` cells.
+    Poisson { parents: f64, radius: f64 },
+    /// Fractional Brownian surface thresholded at the target density; the Hurst
+    /// exponent runs from 0.0 (rough, fragmented) to 1.0 (smooth, large patches).
+    Hurst { hurst: f64 },
+}
+
+impl Placement {
+    /// Name as accepted by `--placement`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Placement::Uniform => "uniform",
+            Placement::Noise { .. } => "noise",
+            Placement::Poisson { .. } => "poisson",
+            Placement::Hurst { .. } => "hurst",
+        }
+    }
+
+    /// Every cell index of the grid, the `trees` cells to plant first.
+    pub fn order<R: Rng + ?Sized>(&self, width: usize, height: usize, trees: usize, rng: &mut R) -> Vec<usize> {
+        match *self {
+            Placement::Uniform => {
+                let mut indices: Vec<usize> = (0..width * height).collect();
+                indices.shuffle(rng);
+                indices
+            }
+            Placement::Noise { scale, octaves } => rank(&fractal_noise(width, height, scale, octaves, rng)),
+            Placement::Poisson { parents, radius } => thomas_order(width, height, trees, parents, radius, rng),
+            Placement::Hurst { hurst } => {
+                // midpoint displacement keeps 2^-H of the displacement per halving
+                let surface = Terrain::diamond_square(width, height, 0.5f64.powf(hurst), rng);
+                let field: Vec<f64> = (0..height)
+                    .flat_map(|y| (0..width).map(move |x| (x, y)))
+                    .map(|(x, y)| surface.get(x, y))
+                    .collect();
+                rank(&field)
+            }
+        }
+    }
+}
+
+/// Cell indices by decreasing field value.
+fn rank(field: &[f64]) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..field.len()).collect();
+    indices.sort_by(|&a, &b| field[b].total_cmp(&field[a]));
+    indices
+}
+
+/// Sum of `octaves` layers of smoothly interpolated lattice noise, each with
+/// half the lattice spacing and half the amplitude of the one before.
+fn fractal_noise<R: Rng + ?Sized>(width: usize, height: usize, scale: f64, octaves: u32, rng: &mut R) -> Vec<f64> {
+    let mut field = vec![0.0; width * height];
+    let mut spacing = scale.max(1.0);
+    let mut amplitude = 1.0;
+    for _ in 0..octaves.max(1) {
+        let columns = (width as f64 / spacing).ceil() as usize + 2;
+        let rows = (height as f64 / spacing).ceil() as usize + 2;
+        let lattice: Vec<f64> = (0..columns * rows).map(|_| rng.random_range(-1.0..1.0)).collect();
+        let smooth = |t: f64| t * t * (3.0 - 2.0 * t);
+
+        for y in 0..height {
+            let fy = y as f64 / spacing;
+            let (row, ty) = (fy as usize, smooth(fy.fract()));
+            for x in 0..width {
+                let fx = x as f64 / spacing;
+                let (column, tx) = (fx as usize, smooth(fx.fract()));
+                let at = |c: usize, r: usize| lattice[r * columns + c];
+                let top = at(column, row) + tx * (at(column + 1, row) - at(column, row));
+                let bottom = at(column, row + 1) + tx * (at(column + 1, row + 1) - at(column, row + 1));
+                field[y * width + x] += amplitude * (top + ty * (bottom - top));
+            }
+        }
+
+        spacing = (spacing / 2.0).max(1.0);
+        amplitude /= 2.0;
+    }
+    field
+}
+
+/// Cells in the order offspring of a Thomas process first land on them. Once `trees`
+/// cells are taken (or the draws run out) the untouched cells follow in random order.
+fn thomas_order<R: Rng + ?Sized>(
+    width: usize,
+    height: usize,
+    trees: usize,
+    parents: f64,
+    radius: f64,
+    rng: &mut R,
+) -> Vec<usize> {
+    let cells = width * height;
+    let mut centers: Vec<usize> = (0..cells).filter(|_| rng.random_bool(parents.clamp(0.0, 1.0))).collect();
+    if centers.is_empty() && cells > 0 {
+        centers.push(rng.random_range(0..cells));
+    }
+
+    let mut taken = vec![false; cells];
+    let mut order = Vec::with_capacity(cells);
+    let mut draws = 0;
+    while order.len() < trees.min(cells) && draws < 20 * cells {
+        draws += 1;
+        let center = centers[rng.random_range(0..centers.len())];
+        // Box–Muller offset around the cell centre
+        let distance = radius * (-2.0 * (1.0 - rng.random::<f64>()).ln()).sqrt();
+        let angle = std::f64::consts::TAU * rng.random::<f64>();
+        let x = ((center % width) as f64 + 0.5 + distance * angle.cos()).floor();
+        let y = ((center / width) as f64 + 0.5 + distance * angle.sin()).floor();
+        if x < 0.0 || y < 0.0 || x >= width as f64 || y >= height as f64 {
+            continue;
+        }
+        let index = y as usize * width + x as usize;
+        if !taken[index] {
+            taken[index] = true;
+            order.push(index);
+        }
+    }
+
+    let mut rest: Vec<usize> = (0..cells).filter(|&i| !taken[i]).collect();
+    rest.shuffle(rng);
+    order.extend(rest);
+    order
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::rng_from_seed;
+
+    const ALL: [Placement; 4] = [
+        Placement::Uniform,
+        Placement::Noise { scale: 8.0, octaves: 4 },
+        Placement::Poisson { parents: 0.005, radius: 3.0 },
+        Placement::Hurst { hurst: 0.7 },
+    ];
+
+    /// Fraction of the 4-neighbors of the first `trees` cells that are trees too.
+    fn neighbor_trees(order: &[usize], width: usize, height: usize, trees: usize) -> f64 {
+        let mut planted = vec![false; width * height];
+        for &index in &order[..trees] {
+            planted[index] = true;
+        }
+        let (mut pairs, mut both) = (0, 0);
+        for &index in &order[..trees] {
+            let (x, y) = (index % width, index / width);
+            for (nx, ny) in [(x + 1, y), (x, y + 1)] {
+                if nx < width && ny < height {
+                    pairs += 1;
+                    both += planted[ny * width + nx] as usize;
+                }
+            }
+        }
+        both as f64 / pairs as f64
+    }
+
+    #[test]
+    fn test_orders_are_permutations() {
+        for placement in ALL {
+            let mut order = placement.order(30, 20, 300, &mut rng_from_seed(2));
+            order.sort_unstable();
+            assert_eq!(order, (0..600).collect::<Vec<_>>(), "{}", placement.name());
+        }
+    }
+
+    #[test]
+    fn test_clustered_placements_are_patchy() {
+        let (width, height, trees) = (80, 80, 2560);
+        let uniform = neighbor_trees(&Placement::Uniform.order(width, height, trees, &mut rng_from_seed(4)), width, height, trees);
+        assert!((uniform - 0.4).abs() < 0.05);
+        for placement in &ALL[1..] {
+            let order = placement.order(width, height, trees, &mut rng_from_seed(4));
+            let clustered = neighbor_trees(&order, width, height, trees);
+            assert!(clustered > uniform + 0.2, "{}: {}", placement.name(), clustered);
+        }
+    }
+
+    #[test]
+    fn test_hurst_controls_smoothness() {
+        let (width, height, trees) = (64, 64, 2048);
+        let smooth = Placement::Hurst { hurst: 1.0 }.order(width, height, trees, &mut rng_from_seed(9));
+        let rough = Placement::Hurst { hurst: 0.0 }.order(width, height, trees, &mut rng_from_seed(9));
+        assert!(neighbor_trees(&smooth, width, height, trees) > neighbor_trees(&rough, width, height, trees));
+    }
+}
@@ -0,0 +1,288 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use crate::forest::Forest;
+use crate::map::{palette, palette_index};
+
+/// Animated image formats a fire can be recorded to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordFormat {
+    Gif,
+    Apng,
+}
+
+impl RecordFormat {
+    /// The format of a file by its extension: `.gif`, or `.png`/`.apng` for APNG.
+    pub fn of(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+            Some("gif") => Some(RecordFormat::Gif),
+            Some("png") | Some("apng") => Some(RecordFormat::Apng),
+            _ => None,
+        }
+    }
+}
+
+/// Where and how to record every fire.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    pub path: String,      // file name with an optional `{run}` placeholder
+    pub delay_ms: u16,     // display time of every frame
+    pub scale: usize,      // pixels per cell side
+    pub max_frames: usize, // longer fires are sampled down to this many frames
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Recording { path: String::new(), delay_ms: 100, scale: 2, max_frames: 500 }
+    }
+}
+
+impl Recording {
+    pub fn path_for(&self, run: usize) -> PathBuf {
+        PathBuf::from(self.path.replace("{run}", &run.to_string()))
+    }
+}
+
+/// Collects the timesteps of a fire without a terminal. Only the cells that changed
+/// are kept per step, so long fires on large grids stay cheap until they are encoded.
+pub struct Recorder {
+    width: usize,
+    height: usize,
+    first: Vec<u8>,               // palette index of every cell in the first frame
+    last: Vec<u8>,                // the same for the latest frame
+    changes: Vec<Vec<(u32, u8)>>, // cells that changed in every later frame
+    watched: Vec<(usize, usize)>, // the cells that were burning in the latest frame
+}
+
+impl Recorder {
+    /// Starts the recording with the forest as it is now.
+    pub fn new(forest: &Forest) -> Self {
+        let first: Vec<u8> = (0..forest.height)
+            .flat_map(|y| (0..forest.width).map(move |x| (x, y)))
+            .map(|(x, y)| palette_index(forest.get(x, y), forest.vegetation(x, y)) as u8)
+            .collect();
+        Recorder {
+            width: forest.width,
+            height: forest.height,
+            last: first.clone(),
+            first,
+            changes: Vec::new(),
+            watched: forest.fire_front().to_vec(),
+        }
+    }
+
+    /// Adds a frame after a timestep. During a fire only the cells burning now or
+    /// one step ago can have changed.
+    pub fn capture(&mut self, forest: &Forest) {
+        let mut changed = Vec::new();
+        for &(x, y) in self.watched.iter().chain(forest.fire_front()) {
+            let index = y * self.width + x;
+            let color = palette_index(forest.get(x, y), forest.vegetation(x, y)) as u8;
+            if self.last[index] != color {
+                self.last[index] = color;
+                changed.push((index as u32, color));
+            }
+        }
+        self.changes.push(changed);
+        self.watched = forest.fire_front().to_vec();
+    }
+
+    pub fn frames(&self) -> usize {
+        self.changes.len() + 1
+    }
+
+    /// Encodes the frames into an animated GIF or APNG, in the format of the file extension.
+    pub fn save(&self, settings: &Recording, path: &Path) -> io::Result<()> {
+        let format = RecordFormat::of(path)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "record to a .gif, .png or .apng file"))?;
+        let out = BufWriter::new(File::create(path)?);
+        let frames = self.frames_to_encode(settings.max_frames);
+        match format {
+            RecordFormat::Gif => self.write_gif(out, settings, &frames),
+            RecordFormat::Apng => self.write_apng(out, settings, &frames),
+        }
+    }
+
+    /// Indices of the frames to encode, evenly spread over the fire, first and last included.
+    fn frames_to_encode(&self, max_frames: usize) -> Vec<usize> {
+        let total = self.frames();
+        if total <= max_frames.max(2) {
+            return (0..total).collect();
+        }
+        let last = (max_frames - 1) as f64;
+        let mut picked: Vec<usize> = (0..max_frames).map(|k| (k as f64 * (total - 1) as f64 / last).round() as usize).collect();
+        picked.dedup();
+        picked
+    }
+
+    /// Calls `emit` with the cells and the changed bounding box `(x0, y0, x1, y1)`
+    /// of every picked frame; the first frame covers the whole grid.
+    fn replay(
+        &self,
+        picked: &[usize],
+        mut emit: impl FnMut(&[u8], (usize, usize, usize, usize)) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let mut cells = self.first.clone();
+        emit(&cells, (0, 0, self.width - 1, self.height - 1))?;
+
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        let mut next = 1;
+        for (frame, changed) in self.changes.iter().enumerate().map(|(i, c)| (i + 1, c)) {
+            for &(index, color) in changed {
+                let index = index as usize;
+                cells[index] = color;
+                let (x, y) = (index % self.width, index / self.width);
+                bounds = Some(bounds.map_or((x, y, x, y), |(x0, y0, x1, y1)| (x0.min(x), y0.min(y), x1.max(x), y1.max(y))));
+            }
+            if picked.get(next) == Some(&frame) {
+                // an unchanged frame still redraws one cell, to keep its place in the timing
+                emit(&cells, bounds.take().unwrap_or((0, 0, 0, 0)))?;
+                next += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Palette indices of the cells in the bounding box, each cell `scale`×`scale` pixels.
+    fn pixels(&self, cells: &[u8], (x0, y0, x1, y1): (usize, usize, usize, usize), scale: usize) -> Vec<u8> {
+        let row_pixels = (x1 - x0 + 1) * scale;
+        let mut pixels = Vec::with_capacity(row_pixels * (y1 - y0 + 1) * scale);
+        for y in y0..=y1 {
+            let start = pixels.len();
+            for &color in &cells[y * self.width + x0..=y * self.width + x1] {
+                pixels.extend(std::iter::repeat_n(color, scale));
+            }
+            for _ in 1..scale {
+                pixels.extend_from_within(start..start + row_pixels);
+            }
+        }
+        pixels
+    }
+
+    fn write_gif(&self, out: impl Write, settings: &Recording, picked: &[usize]) -> io::Result<()> {
+        let scale = settings.scale;
+        let size = |cells: usize| {
+            u16::try_from(cells * scale).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "GIF images are at most 65535 pixels on a side"))
+        };
+        let mut encoder = gif::Encoder::new(out, size(self.width)?, size(self.height)?, &palette())
+            .map_err(io::Error::other)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+
+        self.replay(picked, |cells, (x0, y0, x1, y1)| {
+            let frame = gif::Frame {
+                left: size(x0)?,
+                top: size(y0)?,
+                width: size(x1 - x0 + 1)?,
+                height: size(y1 - y0 + 1)?,
+                delay: (settings.delay_ms / 10).max(1),
+                buffer: self.pixels(cells, (x0, y0, x1, y1), scale).into(),
+                ..gif::Frame::default()
+            };
+            encoder.write_frame(&frame).map_err(io::Error::other)
+        })
+    }
+
+    fn write_apng(&self, out: impl Write, settings: &Recording, picked: &[usize]) -> io::Result<()> {
+        let scale = settings.scale;
+        let mut encoder = png::Encoder::new(out, (self.width * scale) as u32, (self.height * scale) as u32);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(palette());
+        encoder.set_animated(picked.len() as u32, 0)?;
+        encoder.set_frame_delay(settings.delay_ms, 1000)?;
+        let mut writer = encoder.write_header()?;
+
+        self.replay(picked, |cells, (x0, y0, x1, y1)| {
+            writer.reset_frame_position()?;
+            writer.set_frame_dimension(((x1 - x0 + 1) * scale) as u32, ((y1 - y0 + 1) * scale) as u32)?;
+            writer.set_frame_position((x0 * scale) as u32, (y0 * scale) as u32)?;
+            writer.write_image_data(&self.pixels(cells, (x0, y0, x1, y1), scale))?;
+            Ok(())
+        })?;
+        Ok(writer.finish()?)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fire_spread::MooreNeighborhood;
+    use crate::simulation::rng_from_seed;
+
+    /// A recorded fire on a small forest and the burn scar it left.
+    fn recorded_fire() -> (Recorder, Forest) {
+        let mut rng = rng_from_seed(12);
+        let mut forest = Forest::new(24, 16, 0.65, &mut rng);
+        let strike = forest.pick_random_tree(&mut rng);
+        forest.ignite(strike, &mut rng);
+        let mut recorder = Recorder::new(&forest);
+        loop {
+            let burnt_out = forest.fire_spread(&MooreNeighborhood, &mut rng);
+            recorder.capture(&forest);
+            if burnt_out {
+                break;
+            }
+        }
+        (recorder, forest)
+    }
+
+    #[test]
+    fn test_replay_ends_on_the_burn_scar() {
+        let (recorder, forest) = recorded_fire();
+        let scar: Vec<u8> = (0..forest.height)
+            .flat_map(|y| (0..forest.width).map(move |x| (x, y)))
+            .map(|(x, y)| palette_index(forest.get(x, y), forest.vegetation(x, y)) as u8)
+            .collect();
+        assert_eq!(recorder.last, scar);
+
+        let all: Vec<usize> = (0..recorder.frames()).collect();
+        let mut frames = 0;
+        let mut end = Vec::new();
+        recorder.replay(&all, |cells, _| {
+            frames += 1;
+            end = cells.to_vec();
+            Ok(())
+        }).unwrap();
+        assert_eq!(frames, recorder.frames());
+        assert_eq!(end, scar);
+    }
+
+    #[test]
+    fn test_frame_cap_keeps_first_and_last() {
+        let (recorder, _) = recorded_fire();
+        let total = recorder.frames();
+        assert!(total > 6);
+        let picked = recorder.frames_to_encode(5);
+        assert_eq!(picked.len(), 5);
+        assert_eq!((picked[0], picked[4]), (0, total - 1));
+        assert_eq!(recorder.frames_to_encode(1000).len(), total);
+    }
+
+    #[test]
+    fn test_gif_and_apng_files() {
+        let (recorder, _) = recorded_fire();
+        let settings = Recording { max_frames: 4, scale: 3, ..Recording::default() };
+        let dir = std::env::temp_dir();
+
+        let gif_path = dir.join(format!("forest_record_{}.gif", std::process::id()));
+        recorder.save(&settings, &gif_path).unwrap();
+        let mut decoder = gif::DecodeOptions::new().read_info(File::open(&gif_path).unwrap()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (72, 48));
+        let mut frames = 0;
+        while decoder.read_next_frame().unwrap().is_some() {
+            frames += 1;
+        }
+        assert_eq!(frames, 4);
+        std::fs::remove_file(gif_path).unwrap();
+
+        let apng_path = dir.join(format!("forest_record_{}.apng", std::process::id()));
+        recorder.save(&settings, &apng_path).unwrap();
+        let reader = png::Decoder::new(File::open(&apng_path).unwrap()).read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (72, 48));
+        assert_eq!(reader.info().animation_control.unwrap().num_frames, 4);
+        std::fs::remove_file(apng_path).unwrap();
+
+        assert!(recorder.save(&settings, &dir.join("fire.mp4")).is_err());
+    }
+}
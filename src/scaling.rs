@@ -0,0 +1,198 @@
+use rand::Rng;
+use crate::config::Config;
+use crate::simulation::run_simulations;
+use crate::statistics::Proportion;
+use crate::sweep::linspace;
+
+/// Finite-size scaling study: every density of the list on square grids of every side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scaling {
+    pub sizes: Vec<usize>,   // grid sides L
+    pub densities: Vec<f64>, // in increasing order
+}
+
+impl Default for Scaling {
+    fn default() -> Self {
+        Scaling { sizes: Vec::new(), densities: linspace(0.0, 1.0, 41) }
+    }
+}
+
+/// One batch of the study.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScalingPoint {
+    pub size: usize,
+    pub density: f64,
+    pub percolation: Proportion,   // runs whose forest had a cluster spanning the grid
+    pub spanning: Proportion,      // runs whose fire crossed the grid
+    pub mean_burned: f64,          // percent of the trees, mean over the runs
+    pub mean_largest_cluster: f64, // percent of the trees
+}
+
+/// Where the percolation curve of one grid size rises.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeFit {
+    pub size: usize,
+    pub crossing: f64, // density at which the percolation probability first reaches 0.5, interpolated
+    pub mean: f64,     // p_av: mean of the distribution dP/dp of the threshold over the densities
+    pub width: f64,    // Δ: its standard deviation
+}
+
+/// A least-squares parameter and its standard error (NaN with fewer than three points).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub value: f64,
+    pub std_error: f64,
+}
+
+#[derive(Debug)]
+pub struct ScalingResults {
+    pub seed: u64,                  // master seed of every batch
+    pub points: Vec<ScalingPoint>,  // by size, then density
+    pub fits: Vec<SizeFit>,         // by size
+    pub critical_density: Estimate, // p_c of the infinite lattice
+    pub nu: Estimate,               // correlation-length exponent ν
+}
+
+/// Runs a batch for every size and density, then fits the scaling of the percolation
+/// curves, the share of the forests with a spanning cluster against the density; the
+/// spanning of the fires does not sharpen into a step, as above the threshold a fire may
+/// still start in a small cluster. The curves' width shrinks as `Δ ∝ L^(-1/ν)` and their
+/// mean moves as `p_av − p_c ∝ L^(-1/ν)`. `ν` is the negated inverse slope of log Δ
+/// against log L, and `p_c` the intercept of `p_av` against Δ, which vanishes as L
+/// grows. All batches share the master seed.
+pub fn run_scaling(config: &Config, study: &Scaling) -> ScalingResults {
+    let seed = config.seed.unwrap_or_else(|| rand::rng().random());
+    let mut points = Vec::with_capacity(study.sizes.len() * study.densities.len());
+    for &size in &study.sizes {
+        for &density in &study.densities {
+            let batch = Config { width: size, height: size, density, seed: Some(seed), graphics: false, clusters: true, ..config.clone() };
+            let results = run_simulations(&batch);
+            points.push(ScalingPoint {
+                size,
+                density,
+                percolation: results.stats.percolation,
+                spanning: results.stats.spanning,
+                mean_burned: results.stats.mean,
+                mean_largest_cluster: results.stats.mean_largest_cluster,
+            });
+        }
+    }
+
+    let fits: Vec<SizeFit> = study
+        .sizes
+        .iter()
+        .map(|&size| {
+            let curve: Vec<(f64, f64)> = points
+                .iter()
+                .filter(|p| p.size == size && p.percolation.runs > 0)
+                .map(|p| (p.density, p.percolation.estimate()))
+                .collect();
+            fit_curve(size, &curve)
+        })
+        .collect();
+
+    let usable: Vec<&SizeFit> = fits.iter().filter(|f| f.width > 0.0 && f.width.is_finite()).collect();
+    let widths: Vec<(f64, f64)> = usable.iter().map(|f| ((f.size as f64).ln(), f.width.ln())).collect();
+    let nu = match fit_line(&widths) {
+        Some((_, slope)) => Estimate { value: -1.0 / slope.value, std_error: slope.std_error / (slope.value * slope.value) },
+        None => Estimate { value: f64::NAN, std_error: f64::NAN },
+    };
+    let means: Vec<(f64, f64)> = usable.iter().map(|f| (f.width, f.mean)).collect();
+    let critical_density = fit_line(&means).map_or(Estimate { value: f64::NAN, std_error: f64::NAN }, |(intercept, _)| intercept);
+
+    ScalingResults { seed, points, fits, critical_density, nu }
+}
+
+/// Crossing, mean and width of one percolation curve, sorted by density. The rises of the
+/// curve between neighboring densities weigh their midpoints; falls, which are noise, are
+/// left out.
+fn fit_curve(size: usize, curve: &[(f64, f64)]) -> SizeFit {
+    let crossing = curve
+        .windows(2)
+        .find(|w| w[0].1 < 0.5 && w[1].1 >= 0.5)
+        .map_or(f64::NAN, |w| w[0].0 + (0.5 - w[0].1) / (w[1].1 - w[0].1) * (w[1].0 - w[0].0));
+
+    let rises: Vec<(f64, f64)> = curve.windows(2).map(|w| ((w[0].0 + w[1].0) / 2.0, (w[1].1 - w[0].1).max(0.0))).collect();
+    let total: f64 = rises.iter().map(|r| r.1).sum();
+    let mean = rises.iter().map(|(p, w)| p * w).sum::<f64>() / total;
+    let width = (rises.iter().map(|(p, w)| (p - mean).powi(2) * w).sum::<f64>() / total).sqrt();
+    SizeFit { size, crossing, mean, width }
+}
+
+/// Least-squares intercept and slope of `y` against `x` with their standard errors;
+/// None without two distinct `x`.
+fn fit_line(points: &[(f64, f64)]) -> Option<(Estimate, Estimate)> {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if points.len() < 2 || sxx <= 0.0 {
+        return None;
+    }
+    let slope = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum::<f64>() / sxx;
+    let intercept = mean_y - slope * mean_x;
+
+    // residual variance needs a degree of freedom beyond the two parameters
+    let residuals: f64 = points.iter().map(|p| (p.1 - intercept - slope * p.0).powi(2)).sum();
+    let variance = if points.len() > 2 { residuals / (n - 2.0) } else { f64::NAN };
+    let slope_error = (variance / sxx).sqrt();
+    let intercept_error = (variance * (1.0 / n + mean_x * mean_x / sxx)).sqrt();
+    Some((Estimate { value: intercept, std_error: intercept_error }, Estimate { value: slope, std_error: slope_error }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BurnPattern;
+    use crate::fire_spread::VonNeumannNeighborhood;
+
+    #[test]
+    fn test_curve_fit_of_a_step() {
+        // a linear rise from 0.4 to 0.6: mean 0.5, width of a uniform distribution
+        let curve: Vec<(f64, f64)> = linspace(0.3, 0.7, 41)
+            .into_iter()
+            .map(|p| (p, ((p - 0.4) / 0.2).clamp(0.0, 1.0)))
+            .collect();
+        let fit = fit_curve(10, &curve);
+        assert!((fit.crossing - 0.5).abs() < 1e-9);
+        assert!((fit.mean - 0.5).abs() < 1e-9);
+        assert!((fit.width - 0.2 / 12f64.sqrt()).abs() < 2e-3);
+    }
+
+    #[test]
+    fn test_scaling_fit_recovers_known_exponents() {
+        // exact power laws: Δ = L^(-3/4), p_av = 0.6 − 0.5 Δ
+        let points: Vec<(f64, f64)> = [16.0f64, 32.0, 64.0, 128.0].iter().map(|&l| (l.ln(), -0.75 * l.ln())).collect();
+        let (_, slope) = fit_line(&points).unwrap();
+        assert!((-1.0 / slope.value - 4.0 / 3.0).abs() < 1e-9);
+        assert!(slope.std_error < 1e-9);
+
+        let widths = [0.1, 0.05, 0.02];
+        let means: Vec<(f64, f64)> = widths.iter().map(|&w| (w, 0.6 - 0.5 * w)).collect();
+        assert!((fit_line(&means).unwrap().0.value - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_study_runs_every_combination() {
+        let config = Config {
+            simulations: 30,
+            burn_pattern: BurnPattern::VonNeumann(VonNeumannNeighborhood),
+            graphics: false,
+            seed: Some(3),
+            ..Config::default()
+        };
+        let study = Scaling { sizes: vec![16, 32], densities: linspace(0.4, 0.8, 9) };
+        let results = run_scaling(&config, &study);
+
+        assert_eq!(results.points.len(), 18);
+        assert_eq!((results.points[9].size, results.points[9].density), (32, 0.4));
+        for fit in &results.fits {
+            assert!((fit.crossing - 0.6).abs() < 0.08, "{:?}", fit);
+            assert!(fit.width > 0.0);
+        }
+        // the larger grid has the sharper curve
+        assert!(results.fits[1].width < results.fits[0].width);
+        assert!(results.nu.value > 0.0);
+        assert!(results.nu.std_error.is_nan());
+    }
+}
@@ -0,0 +1,286 @@
+use std::sync::Arc;
+use crate::config::Config;
+use crate::display::ForestDisplay;
+use crate::fire_spread::FireSpreadStrategy;
+use crate::forest::{CellState, Forest};
+use crate::simulation::{build_forest, build_terrain, rng_from_seed, run_batch, seed_for_run, with_strategy, SimRng};
+use crate::terrain::Terrain;
+use rand::Rng;
+
+/// Upper bounds (exclusive) of the tree age classes reported each season; the last class is open.
+pub const AGE_CLASS_BOUNDS: [u16; 3] = [10, 20, 50];
+
+/// How burned ground recovers between fires.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Recovery {
+    /// Cover grows back logistically: `r·T·(1 − T/K)` new trees a year on burned cells,
+    /// with `T` the standing trees and `K` the trees before the first fire.
+    Logistic { rate: f64 },
+    /// A burned cell regrows with a yearly chance rising linearly with the years
+    /// since its fire, certain once `maturity` years have passed.
+    Age { maturity: u16 },
+}
+
+impl Recovery {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Recovery::Logistic { .. } => "logistic",
+            Recovery::Age { .. } => "age",
+        }
+    }
+}
+
+/// Multi-season fire regime: one lightning fire a season, then `years` years of recovery.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Seasons {
+    pub seasons: usize,
+    pub years: u16, // recovery years between two fires
+    pub recovery: Recovery,
+}
+
+impl Default for Seasons {
+    fn default() -> Self {
+        Seasons { seasons: 10, years: 10, recovery: Recovery::Logistic { rate: 0.5 } }
+    }
+}
+
+/// One season of a run: its fire and the forest at the end of the recovery.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeasonRecord {
+    pub run: usize,
+    pub season: usize,
+    pub burned: usize,       // trees burned by the season's fire
+    pub cover_before: f64,   // fraction of the grid with trees before the fire
+    pub cover_after: f64,    // fraction of the grid with trees after the recovery
+    pub trees_before: usize,
+    pub mean_age: f64,       // years, over the trees standing after the recovery
+    pub age_classes: [usize; AGE_CLASS_BOUNDS.len() + 1], // trees per age class after the recovery
+}
+
+impl SeasonRecord {
+    /// Trees burned by the fire, in percent of the trees standing before it.
+    pub fn burned_percent(&self) -> f64 {
+        self.burned as f64 / self.trees_before as f64 * 100.0
+    }
+}
+
+/// Every season of every run of a batch, in run then season order.
+#[derive(Debug)]
+pub struct SeasonResults {
+    pub seed: u64, // master seed the batch was run with
+    pub seasons: Vec<SeasonRecord>,
+}
+
+impl SeasonResults {
+    /// Mean over the seasons of the burned percentage, seasons without trees left out.
+    pub fn mean_burned_percent(&self) -> f64 {
+        let burned: Vec<f64> = self.seasons.iter().map(SeasonRecord::burned_percent).filter(|p| p.is_finite()).collect();
+        burned.iter().sum::<f64>() / burned.len() as f64
+    }
+
+    pub fn mean_cover(&self) -> f64 {
+        self.seasons.iter().map(|s| s.cover_after).sum::<f64>() / self.seasons.len() as f64
+    }
+}
+
+/// Labels of the age classes, e.g. "0-9" and "50+".
+pub fn age_class_labels() -> Vec<String> {
+    let mut lower = 0;
+    let mut labels = Vec::new();
+    for upper in AGE_CLASS_BOUNDS {
+        labels.push(format!("{}-{}", lower, upper - 1));
+        lower = upper;
+    }
+    labels.push(format!("{}+", lower));
+    labels
+}
+
+pub fn run_seasons(config: &Config, model: Seasons) -> SeasonResults {
+    let master_seed = config.seed.unwrap_or_else(|| rand::rng().random());
+    let terrain = build_terrain(config, master_seed);
+    let terrain = terrain.as_ref();
+
+    let runs = run_batch(config, |index| {
+        with_strategy(config, terrain, |strategy| run_regime(config, model, terrain, strategy, master_seed, index))
+    });
+
+    SeasonResults { seed: master_seed, seasons: runs.into_iter().flatten().collect() }
+}
+
+/// Burns and regrows one forest for every season of the regime.
+fn run_regime(
+    config: &Config,
+    model: Seasons,
+    terrain: Option<&Arc<Terrain>>,
+    strategy: &dyn FireSpreadStrategy,
+    master_seed: u64,
+    index: usize,
+) -> Vec<SeasonRecord> {
+    let mut rng = rng_from_seed(seed_for_run(master_seed, index));
+    let mut forest = build_forest(config, terrain, &mut rng);
+    let cells = (forest.width * forest.height) as f64;
+    let capacity = forest.total_trees as f64;
+
+    let mut display = config.graphics.then(|| {
+        let mut display = ForestDisplay::new();
+        display.set_shade_elevation(config.shade_elevation);
+        display.prepare_animation();
+        display
+    });
+    let mut show = |forest: &Forest, caption: String| {
+        if let Some(display) = display.as_mut() {
+            display.set_caption(caption);
+            display.render_frame(forest);
+            std::thread::sleep(std::time::Duration::from_millis(config.frame_delay_ms));
+        }
+    };
+
+    let mut records = Vec::with_capacity(model.seasons);
+    for season in 0..model.seasons {
+        let trees_before = forest.tree_count();
+        let burned_before = forest.burned_count;
+
+        if let Some(strike) = forest.pick_random_tree(&mut rng) {
+            forest.ignite(Some(strike), &mut rng);
+            loop {
+                show(&forest, format!("Season {}: fire", season + 1));
+                if forest.fire_spread(strategy, &mut rng) {
+                    break;
+                }
+            }
+        }
+        let burned = forest.burned_count - burned_before;
+
+        for year in 1..=model.years {
+            forest.age_one_year();
+            recover(&mut forest, model.recovery, capacity, &mut rng);
+            show(&forest, format!(
+                "Season {}, recovery year {}: cover {:.3}",
+                season + 1, year, forest.tree_count() as f64 / cells
+            ));
+        }
+
+        let (mean_age, age_classes) = age_structure(&forest);
+        records.push(SeasonRecord {
+            run: index,
+            season,
+            burned,
+            cover_before: trees_before as f64 / cells,
+            cover_after: forest.tree_count() as f64 / cells,
+            trees_before,
+            mean_age,
+            age_classes,
+        });
+    }
+
+    if let Some(mut display) = display {
+        display.tidy_up();
+    }
+    records
+}
+
+/// One year of regrowth on the burned cells.
+fn recover(forest: &mut Forest, recovery: Recovery, capacity: f64, rng: &mut SimRng) -> usize {
+    match recovery {
+        Recovery::Logistic { rate } => {
+            let burned = forest.cells().iter().filter(|&&c| c == CellState::Burned).count();
+            if burned == 0 || capacity == 0.0 {
+                return 0;
+            }
+            // a bare landscape still reseeds from its surroundings at the rate of one tree
+            let trees = (forest.tree_count() as f64).max(1.0);
+            let growth = rate * trees * (1.0 - trees / capacity).max(0.0);
+            let chance = growth / burned as f64;
+            forest.regrow_burned(rng, |_| chance)
+        }
+        Recovery::Age { maturity } => {
+            forest.regrow_burned(rng, |years| years as f64 / maturity.max(1) as f64)
+        }
+    }
+}
+
+/// Mean age of the standing trees and their count per age class.
+fn age_structure(forest: &Forest) -> (f64, [usize; AGE_CLASS_BOUNDS.len() + 1]) {
+    let mut classes = [0; AGE_CLASS_BOUNDS.len() + 1];
+    let mut total_age = 0;
+    let mut trees = 0;
+    for y in 0..forest.height {
+        for x in 0..forest.width {
+            if forest.get(x, y) == CellState::Tree {
+                let age = forest.age(x, y);
+                classes[AGE_CLASS_BOUNDS.partition_point(|&bound| age >= bound)] += 1;
+                total_age += age as usize;
+                trees += 1;
+            }
+        }
+    }
+    (total_age as f64 / trees as f64, classes)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regime_config() -> Config {
+        Config {
+            width: 40,
+            height: 40,
+            density: 0.7,
+            graphics: false,
+            seed: Some(31),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_age_classes() {
+        assert_eq!(age_class_labels(), vec!["0-9", "10-19", "20-49", "50+"]);
+
+        let mut forest = Forest::new(3, 1, 1.0, &mut rng_from_seed(0));
+        for _ in 0..12 {
+            forest.age_one_year();
+        }
+        assert_eq!(age_structure(&forest), (12.0, [0, 3, 0, 0]));
+    }
+
+    #[test]
+    fn test_logistic_recovery_regrows_the_scar() {
+        let model = Seasons { seasons: 5, years: 15, recovery: Recovery::Logistic { rate: 0.8 } };
+        let results = run_seasons(&regime_config(), model);
+        assert_eq!(results.seasons.len(), 5);
+
+        let first = results.seasons[0];
+        assert!((first.cover_before - 0.7).abs() < 1e-9);
+        assert!(first.burned > 0);
+        // the cover comes back towards the original forest but never above it
+        for season in &results.seasons {
+            assert!(season.cover_after <= 0.7 + 1e-9);
+            assert_eq!(season.age_classes.iter().sum::<usize>() as f64, (season.cover_after * 1600.0).round());
+        }
+        assert!(results.seasons[4].cover_after > 0.5);
+    }
+
+    #[test]
+    fn test_age_recovery_needs_time() {
+        let config = regime_config();
+        let quick = run_seasons(&config, Seasons { seasons: 3, years: 5, recovery: Recovery::Age { maturity: 1 } });
+        let slow = run_seasons(&config, Seasons { seasons: 3, years: 5, recovery: Recovery::Age { maturity: 100 } });
+
+        // maturity 1 regrows every scar in the first year
+        assert!(quick.seasons.iter().all(|s| (s.cover_after - 0.7).abs() < 1e-9));
+        assert!(slow.seasons[0].cover_after < quick.seasons[0].cover_after);
+        assert_eq!(quick.seasons[0].burned, slow.seasons[0].burned);
+    }
+
+    #[test]
+    fn test_seeded_regime_repeats() {
+        let model = Seasons::default();
+        let config = Config { simulations: 3, ..regime_config() };
+        let a = run_seasons(&config, model);
+        let b = run_seasons(&Config { threads: 2, ..config }, model);
+        assert_eq!(a.seasons, b.seasons);
+        assert_eq!(a.seasons.len(), 30);
+        assert_eq!(a.seasons[10].run, 1);
+    }
+}
@@ -1,112 +1,355 @@
-use crate::{config::Config, forest::Forest};
-use crate::config::BurnPattern;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use crate::{config::{BurnPattern, Config}, forest::Forest};
 use crate::display::ForestDisplay;
-use crate::fire_spread::FireSpreadStrategy;
+use crate::fire_spread::{FireSpreadStrategy, SlopeStrategy};
+use crate::arrival::{self, ArrivalMap};
+use crate::clusters::Clusters;
+use crate::recording::Recorder;
+use crate::snapshot::SnapshotStep;
+use crate::spanning::Spanning;
+use crate::statistics::{BurnStatistics, StatsAggregator};
+use crate::terrain::{Terrain, TerrainSource};
+use crate::vegetation::Vegetation;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// RNG used for every random decision of a run.
+/// ChaCha8 is portable, so a seed gives the same forest on every platform.
+pub type SimRng = ChaCha8Rng;
 
 #[derive(Debug)]
 pub struct SimulationResults {
     pub average_burned: f64,
     pub min_burned: f64,
     pub max_burned: f64,
+    pub seed: u64, // master seed the batch was run with
+    pub stats: BurnStatistics,
+    pub runs: Option<Vec<RunRecord>>, // only kept with `record_runs`
+    pub cluster_sizes: Vec<(usize, usize)>, // clusters of each size over all runs, by size
+}
+
+/// One finished simulation of a batch. Runs without trees are not recorded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunRecord {
+    pub index: usize,
+    pub seed: u64,
+    pub total_trees: usize,
+    pub burned_count: usize,
+    pub steps: usize,              // timesteps until the fire went out
+    pub peak_front: usize,         // most cells burning at once
+    pub spread_rate: Option<f64>,  // cells per step, None when the fire was too short to measure
+    pub clusters: Option<ClusterRecord>, // tree clusters before the fire, only with `clusters`
+    pub spanning: Spanning,        // edges the burned region crossed
+    pub class_trees: [usize; Vegetation::COUNT],  // trees of each vegetation class
+    pub class_burned: [usize; Vegetation::COUNT], // burned trees of each vegetation class
+    pub ignition: (usize, usize), // the tree struck by lightning
+}
+
+impl RunRecord {
+    pub fn burned_percent(&self) -> f64 {
+        self.burned_count as f64 / self.total_trees as f64 * 100.0
+    }
+
+    pub fn largest_cluster_percent(&self) -> Option<f64> {
+        self.clusters.map(|clusters| clusters.largest as f64 / self.total_trees as f64 * 100.0)
+    }
+}
+
+/// The tree clusters of a run's forest before the fire.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClusterRecord {
+    pub count: usize,     // connected clusters of trees
+    pub largest: usize,   // trees of the largest cluster
+    pub mean_size: f64,   // mean size of the other clusters, see `Clusters::mean_size`
+    pub percolates: bool, // a cluster spanned the grid
+}
+
+pub fn rng_from_seed(seed: u64) -> SimRng {
+    SimRng::seed_from_u64(seed)
+}
+
+/// Derives the seed of simulation `index` from the master seed (SplitMix64 finalizer),
+/// so any run of a batch can be replayed on its own.
+pub fn seed_for_run(master_seed: u64, index: usize) -> u64 {
+    let mut z = master_seed.wrapping_add((index as u64).wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
 }
 
 pub fn run_simulations(config: &Config) -> SimulationResults {
-    let mut total_burned = 0;
-    let mut total_trees = 0;
-    let mut min_burned = f64::MAX;
-    let mut max_burned = f64::MIN;
-
-    let strategy: &dyn FireSpreadStrategy = match &config.burn_pattern {
-        BurnPattern::Moore(s) => s,
-        BurnPattern::VonNeumann(s) => s,
-    };
+    let master_seed = config.seed.unwrap_or_else(|| rand::rng().random());
+    let terrain = build_terrain(config, master_seed);
+    let terrain = terrain.as_ref();
 
-    for _ in 0..config.simulations {
+    let outcomes = run_batch(config, |index| run_single(config, terrain, master_seed, index));
 
-        let mut forest = Forest::new(
-            config.size,
-            config.density,
-        );
+    let mut aggregator = StatsAggregator::new();
+    let mut cluster_sizes = BTreeMap::new();
+    for outcome in &outcomes {
+        match outcome {
+            Some((record, distribution)) => {
+                aggregator.push(record);
+                for &(size, count) in distribution {
+                    *cluster_sizes.entry(size).or_insert(0) += count;
+                }
+            }
+            None => aggregator.skip(), // no trees to burn
+        }
+    }
+
+    let average_burned = aggregator.pooled_burned();
+    let stats = aggregator.finish();
 
-        if forest.total_trees == 0 {
-            continue;
-            // or return - not sure
+    SimulationResults {
+        average_burned,
+        min_burned: stats.min,
+        max_burned: stats.max,
+        seed: master_seed,
+        stats,
+        runs: config.record_runs.then(|| outcomes.into_iter().flatten().map(|(record, _)| record).collect()),
+        cluster_sizes: cluster_sizes.into_iter().collect(),
+    }
+}
+
+/// Runs `run` for every simulation index of the batch (or only the replayed one).
+/// The animation writes to one terminal, so only headless runs are spread over threads.
+pub(crate) fn run_batch<T: Send>(config: &Config, run: impl Fn(usize) -> T + Sync) -> Vec<T> {
+    let runs: Vec<usize> = match config.replay {
+        Some(index) => vec![index],
+        None => (0..config.simulations).collect(),
+    };
+
+    if config.graphics || config.threads <= 1 {
+        runs.into_iter().map(run).collect()
+    } else {
+        run_parallel(config.threads, &runs, &run)
+    }
+}
+
+/// The elevation shared by every run of a batch. Procedural terrain comes from
+/// its own stream of the master seed, so replaying a run burns over the same hills.
+pub(crate) fn build_terrain(config: &Config, master_seed: u64) -> Option<Arc<Terrain>> {
+    match &config.terrain {
+        TerrainSource::Flat => None,
+        TerrainSource::DiamondSquare { roughness } => {
+            let mut rng = rng_from_seed(seed_for_run(master_seed, usize::MAX));
+            Some(Arc::new(Terrain::diamond_square(config.width, config.height, *roughness, &mut rng)))
         }
+        TerrainSource::Heightmap(terrain) => Some(Arc::clone(terrain)),
+    }
+}
+
+/// Splits the runs into one contiguous chunk per worker thread.
+/// Outcomes come back in run order, so the aggregate does not depend on the thread count.
+fn run_parallel<T: Send>(threads: usize, runs: &[usize], run: &(impl Fn(usize) -> T + Sync)) -> Vec<T> {
+    let chunk_size = runs.len().div_ceil(threads).max(1);
 
-        forest.ignite(forest.pick_random_tree());
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = runs
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk.iter()
+                        .map(|&index| run(index))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("simulation thread panicked"))
+            .collect()
+    })
+}
 
-        if config.graphics
-        {
-            let mut display = ForestDisplay::new();
+/// Calls `f` with the spread strategy of the batch, made slope-aware over terrain.
+pub(crate) fn with_strategy<T>(
+    config: &Config,
+    terrain: Option<&Arc<Terrain>>,
+    f: impl FnOnce(&dyn FireSpreadStrategy) -> T,
+) -> T {
+    let base = config.burn_pattern.strategy();
+    match terrain {
+        Some(terrain) => f(&SlopeStrategy { base, terrain, relief: config.relief, factor: config.slope_factor }),
+        None => f(base),
+    }
+}
 
+/// A freshly grown forest with every setting of the config applied.
+pub(crate) fn build_forest(config: &Config, terrain: Option<&Arc<Terrain>>, rng: &mut SimRng) -> Forest {
+    match &config.map {
+        Some(map) => Forest::from_map(map),
+        None => Forest::generate_with(
+            config.width,
+            config.height,
+            &config.vegetation.densities(config.density),
+            config.placement,
+            rng,
+        ),
+    }
+    .with_boundary(config.boundary)
+    .with_spread_prob(config.spread_prob)
+    .with_classes(config.classes)
+    .with_terrain(terrain.cloned())
+}
 
-            println!("forest at the beginning:");
-            println!("{}", forest);
-            println!("{}", forest.density());
-            println!("\n");
-            std::thread::sleep(std::time::Duration::from_millis(1000));
+/// A finished run and the number of tree clusters of each size in its forest.
+type RunOutcome = (RunRecord, Vec<(usize, usize)>);
 
-            display.prepare_animation();
-            loop {
-                display.render_frame(&forest);
+fn run_single(config: &Config, terrain: Option<&Arc<Terrain>>, master_seed: u64, index: usize) -> Option<RunOutcome> {
+    with_strategy(config, terrain, |strategy| burn_single(config, terrain, strategy, master_seed, index))
+}
 
-                if forest.fire_spread(strategy){
-                    break;
-                }
+fn burn_single(
+    config: &Config,
+    terrain: Option<&Arc<Terrain>>,
+    strategy: &dyn FireSpreadStrategy,
+    master_seed: u64,
+    index: usize,
+) -> Option<RunOutcome> {
+    let seed = seed_for_run(master_seed, index);
+    let mut rng = rng_from_seed(seed);
 
-                std::thread::sleep(std::time::Duration::from_millis(config.frame_delay_ms));
-            }
-            display.render_frame(&forest);
+    let mut forest = build_forest(config, terrain, &mut rng);
 
-            display.tidy_up();
+    if forest.total_trees == 0 {
+        return None;
+    }
 
-            println!("forest at the end:");
-            println!("{}", forest);
-            println!("\n");
-        } else {
-            loop {
-                if forest.fire_spread(strategy) {
-                    break;
-                }
+    let snapshot = |forest: &Forest, step| {
+        if let Some(snapshots) = &config.snapshots {
+            snapshots.take(forest, index, step);
+        }
+    };
+    snapshot(&forest, SnapshotStep::Initial);
+    // labeling the clusters costs about as much as the fire itself
+    let clusters = config.clusters.then(|| Clusters::find(&forest, strategy));
+
+    // a mapped fire burns from its own cells, otherwise a forest with trees always has one to strike
+    let strike = match forest.fire_front().first() {
+        Some(&cell) => cell,
+        None => {
+            let strike = forest.pick_random_tree(&mut rng)?;
+            forest.ignite(Some(strike), &mut rng);
+            strike
+        }
+    };
+    let mut steps = 0;
+    let mut peak_front = forest.burning_count();
+    let mut recorder = config.recording.as_ref().map(|_| Recorder::new(&forest));
+
+    if config.graphics
+    {
+        let mut display = ForestDisplay::new();
+        display.set_shade_elevation(config.shade_elevation);
+        if let BurnPattern::Wind(wind) = config.burn_pattern {
+            display.set_caption(wind.to_string());
+        }
+
+
+        println!("forest at the beginning:");
+        println!("{}", forest);
+        println!("{}", forest.density());
+        println!("\n");
+        std::thread::sleep(std::time::Duration::from_millis(1000));
+
+        display.prepare_animation();
+        loop {
+            display.render_frame(&forest);
+
+            steps += 1;
+            let burnt_out = forest.fire_spread(strategy, &mut rng);
+            snapshot(&forest, SnapshotStep::Step(steps));
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.capture(&forest);
+            }
+            if burnt_out {
+                break;
             }
+            peak_front = peak_front.max(forest.burning_count());
 
+            std::thread::sleep(std::time::Duration::from_millis(config.frame_delay_ms));
         }
+        display.render_frame(&forest);
 
-        let burned_percent = forest.burned_count as f64 / forest.total_trees as f64;
+        display.tidy_up();
 
-        total_burned += forest.burned_count;
-        total_trees += forest.total_trees;
+        println!("forest at the end:");
+        println!("{}", forest);
+        println!("\n");
+    } else {
+        loop {
+            steps += 1;
+            let burnt_out = forest.fire_spread(strategy, &mut rng);
+            snapshot(&forest, SnapshotStep::Step(steps));
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.capture(&forest);
+            }
+            if burnt_out {
+                break;
+            }
+            peak_front = peak_front.max(forest.burning_count());
+        }
 
-        if burned_percent < min_burned {
-            min_burned = burned_percent;
+    }
+    snapshot(&forest, SnapshotStep::Final);
+    if let (Some(recording), Some(recorder)) = (&config.recording, recorder) {
+        let path = recording.path_for(index);
+        if let Err(e) = recorder.save(recording, &path) {
+            eprintln!("Cannot save recording {}: {}", path.display(), e);
         }
-        if burned_percent > max_burned {
-            max_burned = burned_percent;
+    }
+    if let Some(path) = &config.arrival {
+        let path = arrival::path_for(path, index);
+        if let Err(e) = ArrivalMap::from_forest(&forest).save(&path) {
+            eprintln!("Cannot save arrival times {}: {}", path.display(), e);
         }
     }
 
-    SimulationResults {
-        average_burned: ( total_burned as f64 / total_trees as f64 ) * 100.0,
-        min_burned: min_burned * 100.0,
-        max_burned: max_burned * 100.0,
-    }
+    let record = RunRecord {
+        index,
+        seed,
+        total_trees: forest.total_trees,
+        burned_count: forest.burned_count,
+        steps,
+        peak_front,
+        spread_rate: arrival::rate_of_spread(&forest),
+        clusters: clusters.as_ref().map(|clusters| ClusterRecord {
+            count: clusters.count(),
+            largest: clusters.largest(),
+            mean_size: clusters.mean_size(),
+            percolates: clusters.spans(),
+        }),
+        spanning: Spanning::of(&forest, strategy),
+        class_trees: forest.class_trees(),
+        class_burned: forest.class_burned(),
+        ignition: strike,
+    };
+    Some((record, clusters.map_or_else(Vec::new, |clusters| clusters.distribution())))
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::fire_spread::MooreNeighborhood;
+    use crate::fire_spread::{Boundary, MooreNeighborhood};
+    use crate::forest::{CellState, Fuel};
+    use crate::map::ForestMap;
+    use crate::snapshot::Snapshots;
 
     #[test]
     fn test_single_simulation() {
         let config = Config {
-            size: 10,
+            width: 10,
+            height: 10,
             density: 0.5,
             simulations: 1,
             burn_pattern: BurnPattern::Moore(MooreNeighborhood),
             graphics: false,
-            frame_delay_ms: 50,
+            ..Config::default()
         };
 
         let results = run_simulations(&config);
@@ -117,15 +360,350 @@ mod tests {
     #[test]
     fn test_zero_simulations() {
         let config = Config {
-            size: 10,
+            width: 10,
+            height: 10,
             density: 0.5,
             simulations: 0,
             burn_pattern: BurnPattern::Moore(MooreNeighborhood),
             graphics: false,
-            frame_delay_ms: 50,
+            ..Config::default()
         };
 
         let results = run_simulations(&config);
         assert!(results.average_burned.is_nan());
     }
+
+    #[test]
+    fn test_seeded_runs_repeat() {
+        let config = Config {
+            width: 40,
+            height: 40,
+            density: 0.45,
+            simulations: 20,
+            graphics: false,
+            seed: Some(1234),
+            ..Config::default()
+        };
+
+        let a = run_simulations(&config);
+        let b = run_simulations(&config);
+        assert_eq!(a.seed, 1234);
+        assert_eq!(a.average_burned, b.average_burned);
+        assert_eq!(a.min_burned, b.min_burned);
+        assert_eq!(a.max_burned, b.max_burned);
+    }
+
+    #[test]
+    fn test_thread_count_does_not_change_results() {
+        let config = Config {
+            width: 30,
+            height: 30,
+            density: 0.5,
+            simulations: 37,
+            graphics: false,
+            seed: Some(5),
+            ..Config::default()
+        };
+
+        let single = run_simulations(&config);
+        for threads in [2, 4, 64] {
+            let parallel = run_simulations(&Config { threads, ..config.clone() });
+            assert_eq!(single.average_burned, parallel.average_burned);
+            assert_eq!(single.min_burned, parallel.min_burned);
+            assert_eq!(single.max_burned, parallel.max_burned);
+            assert_eq!(single.stats.median, parallel.stats.median);
+            assert_eq!(single.stats.std_dev, parallel.stats.std_dev);
+        }
+    }
+
+    #[test]
+    fn test_run_records_are_optional() {
+        let config = Config {
+            width: 20,
+            height: 20,
+            density: 0.5,
+            simulations: 6,
+            graphics: false,
+            seed: Some(3),
+            ..Config::default()
+        };
+        assert!(run_simulations(&config).runs.is_none());
+
+        let results = run_simulations(&Config { record_runs: true, ..config.clone() });
+        let runs = results.runs.unwrap();
+        assert_eq!(runs.len(), 6);
+        for (index, run) in runs.iter().enumerate() {
+            assert_eq!(run.index, index);
+            assert_eq!(run.seed, seed_for_run(3, index));
+            assert_eq!(run.total_trees, 200);
+            assert!(run.burned_count >= 1 && run.steps >= 1);
+        }
+        assert_eq!(results.stats.runs, 6);
+        assert_eq!(results.stats.skipped, 0);
+    }
+
+    #[test]
+    fn test_rectangular_grid() {
+        let config = Config {
+            width: 60,
+            height: 5,
+            density: 0.5,
+            simulations: 3,
+            graphics: false,
+            record_runs: true,
+            ..Config::default()
+        };
+
+        let results = run_simulations(&config);
+        for run in results.runs.unwrap() {
+            assert_eq!(run.total_trees, 150);
+            assert!(run.ignition.0 < 60 && run.ignition.1 < 5);
+        }
+    }
+
+    #[test]
+    fn test_spread_probability_lowers_burn() {
+        let config = Config {
+            width: 50,
+            height: 50,
+            density: 0.9,
+            simulations: 10,
+            graphics: false,
+            seed: Some(4),
+            ..Config::default()
+        };
+
+        let certain = run_simulations(&config);
+        let uncertain = run_simulations(&Config { spread_prob: 0.2, ..config.clone() });
+        assert!(uncertain.average_burned < certain.average_burned);
+    }
+
+    #[test]
+    fn test_empty_forests_are_skipped() {
+        let config = Config {
+            width: 10,
+            height: 10,
+            density: 0.0,
+            simulations: 4,
+            graphics: false,
+            ..Config::default()
+        };
+
+        let results = run_simulations(&config);
+        assert_eq!(results.stats.runs, 0);
+        assert_eq!(results.stats.skipped, 4);
+    }
+
+    #[test]
+    fn test_replay_single_run() {
+        let batch = Config {
+            width: 40,
+            height: 40,
+            density: 0.45,
+            simulations: 20,
+            graphics: false,
+            seed: Some(99),
+            ..Config::default()
+        };
+        let full = run_simulations(&batch);
+
+        // the batch extremes come from single runs, which replay to the same value alone
+        let replayed: Vec<f64> = (0..batch.simulations)
+            .map(|index| run_simulations(&Config { replay: Some(index), ..batch.clone() }).average_burned)
+            .collect();
+        assert!(replayed.contains(&full.min_burned));
+        assert!(replayed.contains(&full.max_burned));
+    }
+
+    #[test]
+    fn test_longer_burns_last_longer() {
+        let config = Config {
+            width: 30,
+            height: 30,
+            density: 0.7,
+            simulations: 5,
+            graphics: false,
+            seed: Some(8),
+            record_runs: true,
+            ..Config::default()
+        };
+        let quick = run_simulations(&config).runs.unwrap();
+        let mut classes = config.classes;
+        classes[Vegetation::Conifer].fuel = Fuel { burn_steps: 4, ..Fuel::default() };
+        let slow = run_simulations(&Config { classes, ..config.clone() }).runs.unwrap();
+
+        for (quick, slow) in quick.iter().zip(&slow) {
+            // certain spread gives the same scar, only the fire lingers
+            assert_eq!(quick.burned_count, slow.burned_count);
+            assert_eq!(slow.steps, quick.steps + 3);
+            assert!(slow.peak_front >= quick.peak_front);
+        }
+    }
+
+    #[test]
+    fn test_burned_area_by_class() {
+        let (vegetation, density) = crate::vegetation::VegetationMix::parse("grass=0.4,deciduous=0.4").unwrap();
+        let config = Config {
+            width: 40,
+            height: 40,
+            density,
+            vegetation,
+            simulations: 6,
+            graphics: false,
+            seed: Some(12),
+            record_runs: true,
+            ..Config::default()
+        };
+
+        let results = run_simulations(&config);
+        for run in results.runs.unwrap() {
+            assert_eq!(run.class_trees[Vegetation::Grass as usize], 640);
+            assert_eq!(run.class_trees[Vegetation::Deciduous as usize], 640);
+            assert_eq!(run.class_burned.iter().sum::<usize>(), run.burned_count);
+        }
+        // grass catches every time, broadleaves only half the time
+        let stats = results.stats;
+        assert!(stats.class_burned_percent(Vegetation::Grass) > stats.class_burned_percent(Vegetation::Deciduous));
+        assert!(stats.class_burned_percent(Vegetation::Conifer).is_nan());
+    }
+
+    #[test]
+    fn test_terrain_is_part_of_the_seeded_batch() {
+        let config = Config {
+            width: 40,
+            height: 40,
+            density: 0.6,
+            simulations: 8,
+            spread_prob: 0.5,
+            terrain: TerrainSource::DiamondSquare { roughness: 0.6 },
+            graphics: false,
+            seed: Some(21),
+            record_runs: true,
+            ..Config::default()
+        };
+
+        let hilly = run_simulations(&config);
+        assert_eq!(hilly.runs, run_simulations(&config).runs);
+        assert_ne!(hilly.runs, run_simulations(&Config { terrain: TerrainSource::Flat, ..config.clone() }).runs);
+
+        // a replayed run sees the same terrain as in the batch
+        let replay = run_simulations(&Config { replay: Some(5), ..config.clone() });
+        assert_eq!(replay.runs.unwrap()[0], hilly.runs.unwrap()[5]);
+    }
+
+    #[test]
+    fn test_snapshots_record_the_burn() {
+        let dir = std::env::temp_dir().join(format!("forest_snapshots_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = Config {
+            width: 15,
+            height: 15,
+            density: 0.7,
+            simulations: 2,
+            graphics: false,
+            seed: Some(5),
+            record_runs: true,
+            snapshots: Some(Snapshots {
+                path: dir.join("run{run}_{step}.bin").display().to_string(),
+                steps: vec![SnapshotStep::Initial, SnapshotStep::Step(1), SnapshotStep::Final],
+            }),
+            ..Config::default()
+        };
+        let runs = run_simulations(&config).runs.unwrap();
+
+        let load = |run: usize, step: &str| ForestMap::load(&dir.join(format!("run{}_{}.bin", run, step))).unwrap();
+        let count = |map: &ForestMap, state| {
+            (0..15).flat_map(|y| (0..15).map(move |x| (x, y))).filter(|&(x, y)| map.get(x, y).0 == state).count()
+        };
+        for run in &runs {
+            assert_eq!(count(&load(run.index, "initial"), CellState::Tree), run.total_trees);
+            assert!(count(&load(run.index, "1"), CellState::Burning) + count(&load(run.index, "1"), CellState::Burned) > 0);
+            assert_eq!(count(&load(run.index, "final"), CellState::Burned), run.burned_count);
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_arrival_times_are_saved() {
+        let path = std::env::temp_dir().join(format!("forest_arrival_run_{}.csv", std::process::id()));
+        let config = Config {
+            width: 20,
+            height: 20,
+            density: 0.9,
+            graphics: false,
+            seed: Some(8),
+            record_runs: true,
+            arrival: Some(path.display().to_string()),
+            ..Config::default()
+        };
+        let run = run_simulations(&config).runs.unwrap()[0];
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        let times: Vec<usize> = csv.lines().flat_map(|line| line.split(',')).filter_map(|t| t.parse().ok()).collect();
+        assert_eq!(csv.lines().count(), 20);
+        assert_eq!(times.len(), run.burned_count);
+        // the last cells to catch fire burn for one more step
+        assert_eq!(times.iter().max(), Some(&(run.steps - 1)));
+        assert!(run.spread_rate.unwrap() > 0.5);
+    }
+
+    #[test]
+    fn test_fire_stays_in_its_cluster() {
+        let config = Config {
+            width: 30,
+            height: 30,
+            density: 0.55,
+            simulations: 12,
+            graphics: false,
+            seed: Some(4),
+            record_runs: true,
+            clusters: true,
+            ..Config::default()
+        };
+        let results = run_simulations(&config);
+        for run in results.runs.as_ref().unwrap() {
+            let clusters = run.clusters.unwrap();
+            assert!(run.burned_count <= clusters.largest);
+            assert!(clusters.count > 1);
+        }
+        let trees: usize = results.cluster_sizes.iter().map(|&(size, count)| size * count).sum();
+        assert_eq!(trees, 12 * 495);
+
+        let full = run_simulations(&Config { density: 1.0, ..config.clone() });
+        assert_eq!(full.cluster_sizes, vec![(900, 12)]);
+        assert_eq!(full.stats.mean_largest_cluster, 100.0);
+        assert_eq!(full.stats.mean_cluster_size, 0.0);
+
+        // without the option no run pays for the labeling
+        let plain = run_simulations(&Config { clusters: false, ..config });
+        assert!(plain.runs.unwrap().iter().all(|run| run.clusters.is_none()));
+        assert!(plain.cluster_sizes.is_empty());
+        assert!(plain.stats.mean_clusters.is_nan());
+        assert_eq!(plain.stats.percolation.runs, 0);
+    }
+
+    #[test]
+    fn test_spanning_probability() {
+        let config = Config {
+            width: 40,
+            height: 40,
+            density: 0.2,
+            simulations: 10,
+            graphics: false,
+            seed: Some(6),
+            clusters: true,
+            ..Config::default()
+        };
+        let sparse = run_simulations(&config).stats;
+        assert_eq!(sparse.spanning.count, 0);
+        assert_eq!(sparse.percolation.count, 0);
+        assert!(sparse.spanning.interval().1 < 0.35);
+
+        let full = run_simulations(&Config { density: 1.0, boundary: Boundary::Periodic, ..config.clone() }).stats;
+        assert_eq!(full.spanning_both.count, 10);
+        assert_eq!(full.percolation.count, 10);
+        assert_eq!(full.wrapping.count, 10);
+        assert_eq!(run_simulations(&Config { density: 1.0, ..config }).stats.wrapping.count, 0);
+    }
 }
@@ -0,0 +1,206 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use crate::forest::Forest;
+use crate::map::{color, encode_cell, symbol, BINARY_MAGIC};
+
+/// File formats of a snapshot; every one of them loads back with `--input-map`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnapshotFormat {
+    Png,    // one pixel per cell in the map palette
+    Ascii,  // one map symbol per cell
+    Csv,    // comma-separated map symbols
+    Binary, // one byte per cell after a small header
+}
+
+impl SnapshotFormat {
+    /// The format of a file by its extension: `.png`, `.bin`, `.csv`, anything else ASCII.
+    pub fn of(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+            Some("png") => SnapshotFormat::Png,
+            Some("bin") => SnapshotFormat::Binary,
+            Some("csv") => SnapshotFormat::Csv,
+            _ => SnapshotFormat::Ascii,
+        }
+    }
+}
+
+/// A moment of a fire worth saving.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnapshotStep {
+    Initial,     // the forest before the lightning strike
+    Step(usize), // the forest after this many timesteps
+    Final,       // the burn scar once the fire is out
+}
+
+impl SnapshotStep {
+    /// Parses "initial", "final" or a timestep.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "initial" => Some(SnapshotStep::Initial),
+            "final" => Some(SnapshotStep::Final),
+            step => step.parse().ok().map(SnapshotStep::Step),
+        }
+    }
+
+    /// Text replacing `{step}` in a snapshot path.
+    pub fn label(&self) -> String {
+        match self {
+            SnapshotStep::Initial => "initial".into(),
+            SnapshotStep::Step(step) => step.to_string(),
+            SnapshotStep::Final => "final".into(),
+        }
+    }
+}
+
+/// Which moments of every fire to save, and where.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshots {
+    pub path: String, // file name with optional `{run}` and `{step}` placeholders
+    pub steps: Vec<SnapshotStep>,
+}
+
+impl Snapshots {
+    pub fn path_for(&self, run: usize, step: SnapshotStep) -> PathBuf {
+        PathBuf::from(self.path.replace("{run}", &run.to_string()).replace("{step}", &step.label()))
+    }
+
+    /// Saves the forest if `step` is one of the snapshot steps. A file that cannot
+    /// be written is reported and skipped, the simulation goes on.
+    pub fn take(&self, forest: &Forest, run: usize, step: SnapshotStep) {
+        if self.steps.contains(&step) {
+            let path = self.path_for(run, step);
+            if let Err(e) = save(forest, &path) {
+                eprintln!("Cannot save snapshot {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Writes the forest to a file in the format of its extension.
+pub fn save(forest: &Forest, path: &Path) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write(forest, SnapshotFormat::of(path), &mut out)?;
+    out.flush()
+}
+
+pub fn write(forest: &Forest, format: SnapshotFormat, out: &mut dyn Write) -> io::Result<()> {
+    match format {
+        SnapshotFormat::Ascii => {
+            for y in 0..forest.height {
+                let row: String = (0..forest.width).map(|x| symbol(forest.get(x, y), forest.vegetation(x, y))).collect();
+                writeln!(out, "{}", row)?;
+            }
+            Ok(())
+        }
+        SnapshotFormat::Csv => {
+            for y in 0..forest.height {
+                let row: Vec<String> =
+                    (0..forest.width).map(|x| symbol(forest.get(x, y), forest.vegetation(x, y)).to_string()).collect();
+                writeln!(out, "{}", row.join(","))?;
+            }
+            Ok(())
+        }
+        SnapshotFormat::Binary => {
+            out.write_all(BINARY_MAGIC)?;
+            out.write_all(&(forest.width as u32).to_le_bytes())?;
+            out.write_all(&(forest.height as u32).to_le_bytes())?;
+            let cells: Vec<u8> = (0..forest.height)
+                .flat_map(|y| (0..forest.width).map(move |x| (x, y)))
+                .map(|(x, y)| encode_cell(forest.get(x, y), forest.vegetation(x, y)))
+                .collect();
+            out.write_all(&cells)
+        }
+        SnapshotFormat::Png => {
+            let mut encoder = png::Encoder::new(out, forest.width as u32, forest.height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&rgb_pixels(forest, 1))?;
+            Ok(writer.finish()?)
+        }
+    }
+}
+
+/// RGB bytes of the forest in the map palette, every cell a `scale`×`scale` square.
+pub fn rgb_pixels(forest: &Forest, scale: usize) -> Vec<u8> {
+    let row_bytes = forest.width * scale * 3;
+    let mut pixels = Vec::with_capacity(row_bytes * forest.height * scale);
+    for y in 0..forest.height {
+        let start = pixels.len();
+        for x in 0..forest.width {
+            let rgb = color(forest.get(x, y), forest.vegetation(x, y));
+            for _ in 0..scale {
+                pixels.extend_from_slice(&rgb);
+            }
+        }
+        for _ in 1..scale {
+            pixels.extend_from_within(start..start + row_bytes);
+        }
+    }
+    pixels
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forest::CellState;
+    use crate::map::ForestMap;
+    use crate::simulation::rng_from_seed;
+    use crate::vegetation::Vegetation;
+
+    #[test]
+    fn test_every_format_loads_back() {
+        let densities = [(Vegetation::Grass, 0.3), (Vegetation::Deciduous, 0.3), (Vegetation::Rock, 0.1)];
+        let mut rng = rng_from_seed(6);
+        let mut forest = Forest::generate(9, 7, &densities, &mut rng);
+        forest.ignite(None, &mut rng);
+        forest.fire_spread(&crate::fire_spread::MooreNeighborhood, &mut rng);
+
+        let dir = std::env::temp_dir();
+        for extension in ["txt", "map", "csv", "png", "bin"] {
+            let path = dir.join(format!("forest_snapshot_{}.{}", std::process::id(), extension));
+            save(&forest, &path).unwrap();
+            let map = ForestMap::load(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!((map.width, map.height), (9, 7));
+            for y in 0..7 {
+                for x in 0..9 {
+                    let (state, vegetation) = map.get(x, y);
+                    assert_eq!(state, forest.get(x, y), "{} at {},{}", extension, x, y);
+                    // ASCII, CSV and PNG keep the class of standing trees, water and rock
+                    if extension == "bin" || state == CellState::Tree || !vegetation.is_fuel() {
+                        assert_eq!(vegetation, forest.vegetation(x, y), "{} at {},{}", extension, x, y);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_paths_and_steps() {
+        let snapshots = Snapshots { path: "out/run{run}_{step}.png".into(), steps: vec![SnapshotStep::Final] };
+        assert_eq!(snapshots.path_for(3, SnapshotStep::Step(12)), PathBuf::from("out/run3_12.png"));
+        assert_eq!(snapshots.path_for(0, SnapshotStep::Initial), PathBuf::from("out/run0_initial.png"));
+
+        assert_eq!(SnapshotStep::parse(" Final"), Some(SnapshotStep::Final));
+        assert_eq!(SnapshotStep::parse("40"), Some(SnapshotStep::Step(40)));
+        assert_eq!(SnapshotStep::parse("last"), None);
+        assert_eq!(SnapshotFormat::of(Path::new("scar.PNG")), SnapshotFormat::Png);
+        assert_eq!(SnapshotFormat::of(Path::new("scar.map")), SnapshotFormat::Ascii);
+        assert_eq!(SnapshotFormat::of(Path::new("scar.csv")), SnapshotFormat::Csv);
+    }
+
+    #[test]
+    fn test_scaled_pixels() {
+        let forest = Forest::new(2, 1, 0.5, &mut rng_from_seed(1));
+        let pixels = rgb_pixels(&forest, 3);
+        assert_eq!(pixels.len(), 2 * 3 * 3 * 3);
+        // every row of a cell's square repeats the first
+        assert_eq!(pixels[..18], pixels[18..36]);
+        assert_eq!(pixels[..3], pixels[6..9]);
+        // one tree, one empty cell
+        assert_ne!(pixels[..3], pixels[9..12]);
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::VecDeque;
+use crate::fire_spread::{Boundary, FireSpreadStrategy};
+use crate::forest::Forest;
+
+/// Whether the cells a fire reached cross the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Spanning {
+    pub left_right: bool, // burned cells on both the left and the right edge
+    pub top_bottom: bool, // burned cells on both the top and the bottom edge
+    pub wraps_x: bool,    // under periodic edges, the burned region closes on itself around the x axis
+    pub wraps_y: bool,    // the same around the y axis
+}
+
+impl Spanning {
+    /// Spanning of the cells that caught fire, connected as the fire spreads under `strategy`.
+    pub fn of(forest: &Forest, strategy: &dyn FireSpreadStrategy) -> Self {
+        let (width, height) = (forest.width, forest.height);
+        let burned = |x: usize, y: usize| forest.arrival(x, y).is_some();
+
+        let mut spanning = Spanning {
+            left_right: (0..height).any(|y| burned(0, y)) && (0..height).any(|y| burned(width - 1, y)),
+            top_bottom: (0..width).any(|x| burned(x, 0)) && (0..width).any(|x| burned(x, height - 1)),
+            ..Spanning::default()
+        };
+        if forest.boundary == Boundary::Periodic {
+            (spanning.wraps_x, spanning.wraps_y) = wrapping(forest, strategy, burned);
+        }
+        spanning
+    }
+
+    pub fn either(&self) -> bool {
+        self.left_right || self.top_bottom
+    }
+
+    pub fn both(&self) -> bool {
+        self.left_right && self.top_bottom
+    }
+
+    pub fn wraps(&self) -> bool {
+        self.wraps_x || self.wraps_y
+    }
+}
+
+/// Walks the cells of a torus picked by `member` keeping unwrapped coordinates; reaching
+/// a cell again at another unwrapped position means a path went once around the grid.
+pub(crate) fn wrapping(
+    forest: &Forest,
+    strategy: &dyn FireSpreadStrategy,
+    member: impl Fn(usize, usize) -> bool,
+) -> (bool, bool) {
+    let (width, height) = (forest.width, forest.height);
+    let bounds = forest.bounds();
+    // shortest displacement between two coordinates on a ring of `len` cells
+    let step = |from: usize, to: usize, len: usize| {
+        let d = to as i64 - from as i64;
+        let half = len as i64 / 2;
+        if d > half { d - len as i64 } else if d < -half { d + len as i64 } else { d }
+    };
+
+    let mut unwrapped: Vec<Option<(i64, i64)>> = vec![None; width * height];
+    let mut queue = VecDeque::new();
+    let (mut wraps_x, mut wraps_y) = (false, false);
+    for start in 0..width * height {
+        let (x, y) = forest.coords(start);
+        if unwrapped[start].is_some() || !member(x, y) {
+            continue;
+        }
+        unwrapped[start] = Some((x as i64, y as i64));
+        queue.push_back((x, y));
+
+        while let Some((x, y)) = queue.pop_front() {
+            let (ux, uy) = unwrapped[forest.index(x, y)].expect("queued cells are placed");
+            strategy.for_each_neighbor(x, y, bounds, &mut |nx, ny| {
+                if !member(nx, ny) {
+                    return;
+                }
+                let position = (ux + step(x, nx, width), uy + step(y, ny, height));
+                match unwrapped[forest.index(nx, ny)] {
+                    None => {
+                        unwrapped[forest.index(nx, ny)] = Some(position);
+                        queue.push_back((nx, ny));
+                    }
+                    Some((px, py)) => {
+                        wraps_x |= px != position.0;
+                        wraps_y |= py != position.1;
+                    }
+                }
+            });
+        }
+    }
+    (wraps_x, wraps_y)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fire_spread::{MooreNeighborhood, VonNeumannNeighborhood};
+    use crate::map::ForestMap;
+    use crate::simulation::rng_from_seed;
+
+    /// Burns a mapped forest from its `*` cells to the end.
+    fn burn(rows: &[&str], boundary: Boundary, strategy: &dyn FireSpreadStrategy) -> Forest {
+        let map = ForestMap::parse_ascii(&rows.join("\n")).unwrap();
+        let mut forest = Forest::from_map(&map).with_boundary(boundary);
+        let mut rng = rng_from_seed(0);
+        while !forest.fire_spread(strategy, &mut rng) {}
+        forest
+    }
+
+    #[test]
+    fn test_crossing_edges() {
+        let rows = ["......", "*TTTTT", "..T...", "..T..."];
+        let forest = burn(&rows, Boundary::Open, &VonNeumannNeighborhood);
+        let spanning = Spanning::of(&forest, &VonNeumannNeighborhood);
+        assert!(spanning.left_right && !spanning.top_bottom);
+        assert!(spanning.either() && !spanning.both() && !spanning.wraps());
+
+        let rows = ["..T...", "*TTTTT", "..T...", "..T..."];
+        let forest = burn(&rows, Boundary::Open, &VonNeumannNeighborhood);
+        assert!(Spanning::of(&forest, &VonNeumannNeighborhood).both());
+    }
+
+    #[test]
+    fn test_wrapping_needs_a_loop_around_the_torus() {
+        // a full row closes on itself across the seam
+        let rows = ["......", "*TTTTT", "......", "..T..."];
+        let forest = burn(&rows, Boundary::Periodic, &VonNeumannNeighborhood);
+        let spanning = Spanning::of(&forest, &VonNeumannNeighborhood);
+        assert!(spanning.wraps_x && !spanning.wraps_y);
+
+        // a row with a gap only touches both edges
+        let rows = ["......", "*TT.TT", "......", "......"];
+        let forest = burn(&rows, Boundary::Periodic, &VonNeumannNeighborhood);
+        let spanning = Spanning::of(&forest, &VonNeumannNeighborhood);
+        assert!(spanning.left_right && !spanning.wraps());
+
+        // a diagonal band wraps under Moore in both directions at once
+        let rows = ["*...", ".T..", "..T.", "...T"];
+        let forest = burn(&rows, Boundary::Periodic, &MooreNeighborhood);
+        let spanning = Spanning::of(&forest, &MooreNeighborhood);
+        assert!(spanning.wraps_x && spanning.wraps_y);
+    }
+}
@@ -0,0 +1,308 @@
+use crate::simulation::RunRecord;
+use crate::spanning::Spanning;
+use crate::vegetation::Vegetation;
+
+/// z value of the two-sided 95% normal confidence interval
+pub(crate) const Z_95: f64 = 1.959_963_984_540_054;
+
+/// Summary of the runs of one batch. Burn values are percentages of the trees of a run.
+/// With no completed runs every value is NaN.
+#[derive(Debug, Clone, Copy)]
+pub struct BurnStatistics {
+    pub runs: usize,
+    pub skipped: usize, // runs without a single tree
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64, // sample standard deviation
+    pub median: f64,
+    pub p05: f64,
+    pub p25: f64,
+    pub p75: f64,
+    pub p95: f64,
+    pub ci95: f64, // half width of the 95% confidence interval on the mean
+    pub mean_steps: f64,
+    pub max_steps: usize,
+    pub mean_peak_front: f64, // most cells burning at once, averaged over the runs
+    pub max_peak_front: usize,
+    pub mean_spread_rate: f64, // cells per step, over the runs long enough to measure it
+    pub mean_clusters: f64,        // the cluster figures are NaN unless the runs labeled their clusters
+    pub mean_largest_cluster: f64, // percent of the trees in the largest cluster, averaged over the runs
+    pub mean_cluster_size: f64,    // susceptibility averaged over the runs
+    pub spanning: Proportion,            // runs whose fire crossed the grid either way
+    pub spanning_left_right: Proportion,
+    pub spanning_top_bottom: Proportion,
+    pub spanning_both: Proportion,
+    pub wrapping: Proportion, // runs whose fire wrapped around periodic edges
+    pub percolation: Proportion, // runs whose forest had a cluster spanning the grid, of those labeled
+    pub class_trees: [usize; Vegetation::COUNT],  // trees of each class over all runs
+    pub class_burned: [usize; Vegetation::COUNT], // burned trees of each class over all runs
+}
+
+/// Share of the runs with some outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Proportion {
+    pub count: usize,
+    pub runs: usize,
+}
+
+impl Proportion {
+    /// The observed share, NaN without runs.
+    pub fn estimate(&self) -> f64 {
+        self.count as f64 / self.runs as f64
+    }
+
+    /// 95% Wilson score interval of the share; unlike the normal approximation it
+    /// stays within 0..1 and is not empty when every run (or none) had the outcome.
+    pub fn interval(&self) -> (f64, f64) {
+        if self.runs == 0 {
+            return (f64::NAN, f64::NAN);
+        }
+        let n = self.runs as f64;
+        let p = self.estimate();
+        let z2 = Z_95 * Z_95;
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let half = Z_95 / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+        ((center - half).max(0.0), (center + half).min(1.0))
+    }
+
+    fn push(&mut self, outcome: bool) {
+        self.count += outcome as usize;
+        self.runs += 1;
+    }
+}
+
+impl BurnStatistics {
+    /// Burned trees of a vegetation class over all its trees of the batch, in percent.
+    /// NaN when the class never grew.
+    pub fn class_burned_percent(&self, class: Vegetation) -> f64 {
+        self.class_burned[class as usize] as f64 / self.class_trees[class as usize] as f64 * 100.0
+    }
+}
+
+/// Streaming aggregator of run records.
+///
+/// Mean and variance are updated with Welford's algorithm as records arrive;
+/// only the burned percentage of each run is kept, for the median and percentiles.
+#[derive(Debug, Default)]
+pub struct StatsAggregator {
+    burned: Vec<f64>,
+    mean: f64,
+    m2: f64,
+    total_burned: usize,
+    total_trees: usize,
+    total_steps: usize,
+    max_steps: usize,
+    total_peak_front: usize,
+    max_peak_front: usize,
+    total_spread_rate: f64,
+    spread_rates: usize, // runs with a measured rate of spread
+    cluster_runs: usize, // runs whose clusters were labeled
+    total_clusters: usize,
+    total_largest_cluster: f64,
+    total_cluster_size: f64,
+    spanning: [Proportion; 5], // either, left-right, top-bottom, both, wrapping
+    percolation: Proportion,
+    class_trees: [usize; Vegetation::COUNT],
+    class_burned: [usize; Vegetation::COUNT],
+    skipped: usize,
+}
+
+impl StatsAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, record: &RunRecord) {
+        let value = record.burned_percent();
+        self.burned.push(value);
+
+        let delta = value - self.mean;
+        self.mean += delta / self.burned.len() as f64;
+        self.m2 += delta * (value - self.mean);
+
+        self.total_burned += record.burned_count;
+        self.total_trees += record.total_trees;
+        self.total_steps += record.steps;
+        self.max_steps = self.max_steps.max(record.steps);
+        self.total_peak_front += record.peak_front;
+        self.max_peak_front = self.max_peak_front.max(record.peak_front);
+        let Spanning { left_right, top_bottom, .. } = record.spanning;
+        let outcomes = [record.spanning.either(), left_right, top_bottom, record.spanning.both(), record.spanning.wraps()];
+        for (proportion, outcome) in self.spanning.iter_mut().zip(outcomes) {
+            proportion.push(outcome);
+        }
+        if let (Some(clusters), Some(largest)) = (record.clusters, record.largest_cluster_percent()) {
+            self.cluster_runs += 1;
+            self.total_clusters += clusters.count;
+            self.total_largest_cluster += largest;
+            self.total_cluster_size += clusters.mean_size;
+            self.percolation.push(clusters.percolates);
+        }
+        if let Some(rate) = record.spread_rate {
+            self.total_spread_rate += rate;
+            self.spread_rates += 1;
+        }
+        for class in 0..Vegetation::COUNT {
+            self.class_trees[class] += record.class_trees[class];
+            self.class_burned[class] += record.class_burned[class];
+        }
+    }
+
+    /// Counts a run that was skipped because its forest had no trees.
+    pub fn skip(&mut self) {
+        self.skipped += 1;
+    }
+
+    /// Burned trees over all trees of the batch, in percent.
+    pub fn pooled_burned(&self) -> f64 {
+        self.total_burned as f64 / self.total_trees as f64 * 100.0
+    }
+
+    pub fn finish(mut self) -> BurnStatistics {
+        let n = self.burned.len();
+        self.burned.sort_by(f64::total_cmp);
+
+        let (mean, std_dev) = match n {
+            0 => (f64::NAN, f64::NAN),
+            1 => (self.mean, 0.0),
+            _ => (self.mean, (self.m2 / (n - 1) as f64).sqrt()),
+        };
+
+        BurnStatistics {
+            runs: n,
+            skipped: self.skipped,
+            min: percentile(&self.burned, 0.0),
+            max: percentile(&self.burned, 1.0),
+            mean,
+            std_dev,
+            median: percentile(&self.burned, 0.5),
+            p05: percentile(&self.burned, 0.05),
+            p25: percentile(&self.burned, 0.25),
+            p75: percentile(&self.burned, 0.75),
+            p95: percentile(&self.burned, 0.95),
+            ci95: Z_95 * std_dev / (n as f64).sqrt(),
+            mean_steps: self.total_steps as f64 / n as f64,
+            max_steps: self.max_steps,
+            mean_peak_front: self.total_peak_front as f64 / n as f64,
+            max_peak_front: self.max_peak_front,
+            mean_spread_rate: self.total_spread_rate / self.spread_rates as f64,
+            mean_clusters: self.total_clusters as f64 / self.cluster_runs as f64,
+            mean_largest_cluster: self.total_largest_cluster / self.cluster_runs as f64,
+            mean_cluster_size: self.total_cluster_size / self.cluster_runs as f64,
+            spanning: self.spanning[0],
+            spanning_left_right: self.spanning[1],
+            spanning_top_bottom: self.spanning[2],
+            spanning_both: self.spanning[3],
+            wrapping: self.spanning[4],
+            percolation: self.percolation,
+            class_trees: self.class_trees,
+            class_burned: self.class_burned,
+        }
+    }
+}
+
+/// Percentile `q` (0.0 to 1.0) of sorted values, interpolating linearly between ranks.
+pub fn percentile(sorted: &[f64], q: f64) -> f64 {
+    match sorted.len() {
+        0 => f64::NAN,
+        1 => sorted[0],
+        n => {
+            let rank = q.clamp(0.0, 1.0) * (n - 1) as f64;
+            let lower = rank.floor() as usize;
+            let upper = rank.ceil() as usize;
+            sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::ClusterRecord;
+
+    fn record(burned_count: usize, steps: usize) -> RunRecord {
+        RunRecord {
+            index: 0,
+            seed: 0,
+            total_trees: 10,
+            burned_count,
+            steps,
+            peak_front: steps * 2,
+            spread_rate: (steps > 1).then_some(steps as f64 / 2.0),
+            clusters: Some(ClusterRecord { count: steps, largest: burned_count, mean_size: 1.5, percolates: burned_count > 5 }),
+            spanning: Spanning { left_right: burned_count > 4, top_bottom: burned_count > 6, ..Spanning::default() },
+            class_trees: [0, 0, 10, 0, 0, 0],
+            class_burned: [0, 0, burned_count, 0, 0, 0],
+            ignition: (0, 0),
+        }
+    }
+
+    #[test]
+    fn test_summary_of_known_values() {
+        let mut stats = StatsAggregator::new();
+        for (burned, steps) in [(2, 3), (4, 5), (4, 5), (4, 1), (5, 6), (5, 2), (7, 4), (9, 10)] {
+            stats.push(&record(burned, steps));
+        }
+        stats.skip();
+        assert!((stats.pooled_burned() - 50.0).abs() < 1e-9);
+
+        let summary = stats.finish();
+        assert_eq!(summary.runs, 8);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.min, 20.0);
+        assert_eq!(summary.max, 90.0);
+        assert!((summary.mean - 50.0).abs() < 1e-9);
+        assert!((summary.std_dev - (3200.0f64 / 7.0).sqrt()).abs() < 1e-9);
+        assert!((summary.ci95 - Z_95 * summary.std_dev / 8.0f64.sqrt()).abs() < 1e-9);
+        assert!((summary.median - 45.0).abs() < 1e-9);
+        assert!((summary.mean_steps - 4.5).abs() < 1e-9);
+        assert_eq!(summary.max_steps, 10);
+        assert!((summary.mean_peak_front - 9.0).abs() < 1e-9);
+        assert_eq!(summary.max_peak_front, 20);
+        assert!((summary.mean_spread_rate - 2.5).abs() < 1e-9);
+        assert!((summary.mean_clusters - 4.5).abs() < 1e-9);
+        assert!((summary.mean_largest_cluster - 50.0).abs() < 1e-9);
+        assert!((summary.mean_cluster_size - 1.5).abs() < 1e-9);
+        assert_eq!(summary.spanning, Proportion { count: 4, runs: 8 });
+        assert_eq!(summary.spanning_both.count, 2);
+        assert_eq!(summary.wrapping.estimate(), 0.0);
+        assert_eq!(summary.percolation, Proportion { count: 2, runs: 8 });
+        assert!((summary.class_burned_percent(Vegetation::Conifer) - 50.0).abs() < 1e-9);
+        assert!(summary.class_burned_percent(Vegetation::Grass).is_nan());
+    }
+
+    #[test]
+    fn test_wilson_interval() {
+        // 8 of 20: 0.4 with the interval [0.219, 0.613]
+        let (low, high) = Proportion { count: 8, runs: 20 }.interval();
+        assert!((low - 0.2188).abs() < 1e-4 && (high - 0.6134).abs() < 1e-4);
+
+        let (low, high) = Proportion { count: 10, runs: 10 }.interval();
+        assert!(low > 0.69 && low < 0.73);
+        assert!((high - 1.0).abs() < 1e-9);
+        assert!(Proportion::default().interval().0.is_nan());
+    }
+
+    #[test]
+    fn test_percentile_interpolates() {
+        let values = [10.0, 20.0, 30.0, 40.0, 50.0];
+        assert_eq!(percentile(&values, 0.0), 10.0);
+        assert_eq!(percentile(&values, 0.5), 30.0);
+        assert_eq!(percentile(&values, 0.625), 35.0);
+        assert_eq!(percentile(&values, 1.0), 50.0);
+        assert!(percentile(&[], 0.5).is_nan());
+    }
+
+    #[test]
+    fn test_empty_batch_is_nan() {
+        let summary = StatsAggregator::new().finish();
+        assert_eq!(summary.runs, 0);
+        assert!(summary.mean.is_nan());
+        assert!(summary.median.is_nan());
+        assert!(summary.ci95.is_nan());
+        assert!(summary.mean_spread_rate.is_nan());
+        assert!(summary.mean_largest_cluster.is_nan());
+    }
+}
@@ -0,0 +1,287 @@
+use crate::config::{parse_size, BurnPattern, Config};
+use crate::fire_spread::WindStrategy;
+
+/// Step between densities of `--auto-sweep` without `--sweep-step`.
+pub const DEFAULT_SWEEP_STEP: f64 = 0.05;
+
+/// One swept parameter and the values it takes.
+#[derive(Debug, Clone)]
+pub enum Axis {
+    Density(Vec<f64>),
+    Size(Vec<(usize, usize)>), // width and height
+    BurnPattern(Vec<BurnPattern>),
+    Simulations(Vec<usize>),
+}
+
+impl Axis {
+    /// Parses `<parameter>=<values>`, the values a comma-separated list or, for numbers,
+    /// a `start:stop:num` range; a range rounded to whole numbers keeps each of them once.
+    /// `wind` is the wind of a `wind` burn pattern.
+    pub fn parse(spec: &str, wind: WindStrategy) -> Result<Self, String> {
+        let (name, values) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid sweep '{}'; use <parameter>=<values>", spec))?;
+        let axis = match name.trim().to_lowercase().as_str() {
+            "density" => Axis::Density(parse_densities(values)?),
+            "size" => Axis::Size(match parse_range(values)? {
+                Some(sides) => rounded(&sides).into_iter().map(|side| (side, side)).collect(),
+                None => values.split(',').map(parse_size).collect::<Result<_, _>>()?,
+            }),
+            "burn-pattern" => Axis::BurnPattern(
+                values
+                    .split(',')
+                    .map(|name| BurnPattern::parse(name.trim(), wind).ok_or_else(|| format!("Invalid burn pattern '{}'", name)))
+                    .collect::<Result<_, _>>()?,
+            ),
+            "simulations" => Axis::Simulations(match parse_range(values)? {
+                Some(counts) => rounded(&counts),
+                None => values
+                    .split(',')
+                    .map(|count| count.trim().parse().map_err(|_| format!("Invalid simulation count '{}'", count)))
+                    .collect::<Result<_, _>>()?,
+            }),
+            _ => return Err(format!("Cannot sweep '{}'. Use density, size, burn-pattern or simulations", name)),
+        };
+        if axis.is_empty() {
+            return Err(format!("Sweep of {} has no values", axis.name()));
+        }
+        if matches!(&axis, Axis::Size(sizes) if sizes.iter().any(|&(w, h)| w == 0 || h == 0))
+            || matches!(&axis, Axis::Simulations(counts) if counts.contains(&0))
+        {
+            return Err(format!("Sweep of {} needs values of at least 1", axis.name()));
+        }
+        Ok(axis)
+    }
+
+    /// Name as accepted by `--sweep`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Axis::Density(_) => "density",
+            Axis::Size(_) => "size",
+            Axis::BurnPattern(_) => "burn-pattern",
+            Axis::Simulations(_) => "simulations",
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Axis::Density(values) => values.len(),
+            Axis::Size(values) => values.len(),
+            Axis::BurnPattern(values) => values.len(),
+            Axis::Simulations(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sets the parameter of `config` to the value at `index`.
+    fn apply(&self, index: usize, config: &mut Config) {
+        match self {
+            Axis::Density(values) => config.density = values[index],
+            Axis::Size(values) => (config.width, config.height) = values[index],
+            Axis::BurnPattern(values) => config.burn_pattern = values[index],
+            Axis::Simulations(values) => config.simulations = values[index],
+        }
+    }
+
+    /// The value of the parameter in `config`, for a text row.
+    pub fn describe(&self, config: &Config) -> String {
+        match self {
+            Axis::Density(densities) => format!("density: {:.*}", decimals(densities), config.density),
+            Axis::Size(_) => format!("size: {}x{}", config.width, config.height),
+            Axis::BurnPattern(_) => format!("burn pattern: {}", config.burn_pattern.name()),
+            Axis::Simulations(_) => format!("simulations: {}", config.simulations),
+        }
+    }
+}
+
+/// Parameters swept together: every combination of their values is one point.
+#[derive(Debug, Clone, Default)]
+pub struct Sweep {
+    pub axes: Vec<Axis>, // the first varies slowest
+}
+
+impl Sweep {
+    /// Adds an axis, replacing an earlier one of the same parameter.
+    pub fn push(&mut self, axis: Axis) {
+        match self.axes.iter_mut().find(|a| a.name() == axis.name()) {
+            Some(existing) => *existing = axis,
+            None => self.axes.push(axis),
+        }
+    }
+
+    pub fn axis(&self, name: &str) -> Option<&Axis> {
+        self.axes.iter().find(|axis| axis.name() == name)
+    }
+
+    /// Number of combinations.
+    pub fn points(&self) -> usize {
+        self.axes.iter().map(Axis::len).product()
+    }
+
+    /// The config of every combination, `base` with the swept parameters set, in
+    /// row-major order of the axes.
+    pub fn combinations(&self, base: &Config) -> Vec<Config> {
+        (0..self.points())
+            .map(|mut point| {
+                let mut config = base.clone();
+                for axis in self.axes.iter().rev() {
+                    axis.apply(point % axis.len(), &mut config);
+                    point /= axis.len();
+                }
+                config
+            })
+            .collect()
+    }
+}
+
+/// Densities of `--auto-sweep`: every multiple of `step` from `step` up to 1, each
+/// computed from its index so that no rounding error builds up along the sweep.
+pub fn density_steps(step: f64) -> Vec<f64> {
+    let steps = 1.0 / step;
+    if (steps - steps.round()).abs() < 1e-9 {
+        // `i / n` is the density closest to the decimal step, e.g. exactly 0.07
+        return linspace(0.0, 1.0, steps.round() as usize + 1).split_off(1);
+    }
+    (1..=(steps + 1e-9).floor() as usize).map(|i| i as f64 * step).collect()
+}
+
+/// `num` evenly spaced values from `start` to `stop`, both included. Every value is
+/// computed from its index, so the last one is exactly `stop`.
+pub fn linspace(start: f64, stop: f64, num: usize) -> Vec<f64> {
+    match num {
+        0 => Vec::new(),
+        1 => vec![start],
+        _ => (0..num).map(|i| if i + 1 == num { stop } else { start + (stop - start) * i as f64 / (num - 1) as f64 }).collect(),
+    }
+}
+
+/// Whole numbers of an increasing range, each once.
+fn rounded(values: &[f64]) -> Vec<usize> {
+    let mut values: Vec<usize> = values.iter().map(|&value| value.round() as usize).collect();
+    values.dedup();
+    values
+}
+
+/// Decimals that print every density of an axis as it is, at least two; for values
+/// no short decimal holds, like thirds, the fewest that still tell them apart.
+fn decimals(densities: &[f64]) -> usize {
+    let round = |value: f64, decimals: usize| {
+        let scale = 10f64.powi(decimals as i32);
+        (value * scale).round() / scale
+    };
+    (2..=6)
+        .find(|&d| densities.iter().all(|&density| (density - round(density, d)).abs() < 1e-9))
+        .or_else(|| (2..9).find(|&d| densities.windows(2).all(|pair| round(pair[0], d) != round(pair[1], d))))
+        .unwrap_or(9)
+}
+
+/// The points of a `start:stop:num` range, or None when `spec` is no range.
+fn parse_range(spec: &str) -> Result<Option<Vec<f64>>, String> {
+    let invalid = || format!("Invalid range '{}'; use start:stop:num", spec);
+    match spec.split(':').collect::<Vec<_>>()[..] {
+        [start, stop, num] => {
+            let start: f64 = start.trim().parse().map_err(|_| invalid())?;
+            let stop: f64 = stop.trim().parse().map_err(|_| invalid())?;
+            let num: usize = num.trim().parse().map_err(|_| invalid())?;
+            Ok(Some(linspace(start, stop, num)))
+        }
+        [_] => Ok(None),
+        _ => Err(invalid()),
+    }
+}
+
+/// Parses a comma-separated list of densities or a `start:stop:num` range, sorted.
+pub fn parse_densities(spec: &str) -> Result<Vec<f64>, String> {
+    let mut densities = match parse_range(spec)? {
+        Some(densities) => densities,
+        None => spec
+            .split(',')
+            .map(|d| d.trim().parse().map_err(|_| format!("Invalid densities '{}'; use a list like 0.5,0.6 or start:stop:num", spec)))
+            .collect::<Result<Vec<f64>, _>>()?,
+    };
+    if densities.is_empty() || densities.iter().any(|d| !(0.0..=1.0).contains(d)) {
+        return Err("Densities must be between 0.0 and 1.0".into());
+    }
+    densities.sort_by(f64::total_cmp);
+    densities.dedup();
+    Ok(densities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_grid_points() {
+        let densities = linspace(0.1, 0.7, 7);
+        assert_eq!(densities.len(), 7);
+        assert_eq!(densities[6], 0.7);
+        assert!((densities[3] - 0.4).abs() < 1e-12);
+
+        assert_eq!(parse_densities("0.5:0.6:3").unwrap().len(), 3);
+        assert_eq!(parse_densities("0.4, 0.2,0.4").unwrap(), vec![0.2, 0.4]);
+        assert!(parse_densities("0.5:1.5:3").is_err());
+        assert!(parse_densities("0.5:0.6").is_err());
+        assert!(parse_densities("dense").is_err());
+
+        // 100 steps of 0.01 land on 1.0 exactly, where adding them up falls short
+        let steps = density_steps(0.01);
+        assert_eq!(steps.len(), 100);
+        assert_eq!((steps[0], steps[6], steps[99]), (0.01, 0.07, 1.0));
+        let steps = density_steps(0.03);
+        assert_eq!(steps.len(), 33);
+        assert!((steps[32] - 0.99).abs() < 1e-12);
+        // rows print the density rounded, not its binary expansion
+        let row = Axis::Density(steps.clone()).describe(&Config { density: steps[6], ..Config::default() });
+        assert_eq!(row, "density: 0.21");
+        // as finely as the axis steps
+        let fine = parse_densities("0.58:0.60:21").unwrap();
+        let rows: Vec<String> =
+            fine.iter().map(|&density| Axis::Density(fine.clone()).describe(&Config { density, ..Config::default() })).collect();
+        assert_eq!((rows[1].as_str(), rows[20].as_str()), ("density: 0.581", "density: 0.600"));
+        let thirds = linspace(0.0, 1.0, 4);
+        assert_eq!(Axis::Density(thirds.clone()).describe(&Config { density: thirds[1], ..Config::default() }), "density: 0.33");
+    }
+
+    #[test]
+    fn test_axes() {
+        let wind = WindStrategy::default();
+        let sizes = Axis::parse("size=32,64x16", wind).unwrap();
+        assert!(matches!(&sizes, Axis::Size(v) if v == &[(32, 32), (64, 16)]));
+        let sides = Axis::parse("size=16:64:4", wind).unwrap();
+        assert!(matches!(&sides, Axis::Size(v) if v == &[(16, 16), (32, 32), (48, 48), (64, 64)]));
+        let repeated = Axis::parse("size=16:17:4", wind).unwrap();
+        assert!(matches!(&repeated, Axis::Size(v) if v == &[(16, 16), (17, 17)]));
+        assert!(matches!(Axis::parse("simulations=10:11:5", wind).unwrap(), Axis::Simulations(v) if v == [10, 11]));
+        let patterns = Axis::parse("burn-pattern=moore,vonneumann,wind", wind).unwrap();
+        assert_eq!(patterns.len(), 3);
+        assert!(matches!(Axis::parse("simulations=10,100", wind).unwrap(), Axis::Simulations(v) if v == [10, 100]));
+
+        assert!(Axis::parse("density", wind).is_err());
+        assert!(Axis::parse("spread=0.5", wind).is_err());
+        assert!(Axis::parse("size=0,8", wind).is_err());
+        assert!(Axis::parse("burn-pattern=hex", wind).is_err());
+        assert!(Axis::parse("simulations=0:10:3", wind).is_err());
+    }
+
+    #[test]
+    fn test_cartesian_product() {
+        let wind = WindStrategy::default();
+        let mut sweep = Sweep::default();
+        sweep.push(Axis::parse("density=0.2,0.4,0.6", wind).unwrap());
+        sweep.push(Axis::parse("burn-pattern=moore,vonneumann", wind).unwrap());
+        sweep.push(Axis::parse("density=0.3,0.5", wind).unwrap());
+        assert_eq!(sweep.axes.len(), 2);
+        assert_eq!(sweep.points(), 4);
+
+        let points: Vec<(f64, &str)> = sweep
+            .combinations(&Config::default())
+            .iter()
+            .map(|config| (config.density, config.burn_pattern.name()))
+            .collect();
+        assert_eq!(points, vec![(0.3, "moore"), (0.3, "vonneumann"), (0.5, "moore"), (0.5, "vonneumann")]);
+    }
+}
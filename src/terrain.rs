@@ -0,0 +1,223 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use rand::Rng;
+
+/// Elevation layer under the forest, normalized to `0.0..=1.0`, row-major like the cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Terrain {
+    pub width: usize,
+    pub height: usize,
+    elevation: Vec<f64>,
+}
+
+/// Where the elevation of a batch comes from.
+#[derive(Debug, Clone, Default)]
+pub enum TerrainSource {
+    #[default]
+    Flat,
+    /// Procedural diamond-square terrain, regenerated from the master seed of each batch.
+    DiamondSquare { roughness: f64 },
+    /// A heightmap loaded once from a file.
+    Heightmap(Arc<Terrain>),
+}
+
+impl TerrainSource {
+    /// Name as accepted by `--terrain`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TerrainSource::Flat => "flat",
+            TerrainSource::DiamondSquare { .. } => "diamond-square",
+            TerrainSource::Heightmap(_) => "heightmap",
+        }
+    }
+}
+
+impl Terrain {
+    /// Builds a terrain from raw heights, rescaled to `0.0..=1.0`.
+    pub fn from_heights(width: usize, height: usize, heights: Vec<f64>) -> Result<Self, String> {
+        if width == 0 || height == 0 || heights.len() != width * height {
+            return Err(format!("Heightmap needs {}x{} values, got {}", width, height, heights.len()));
+        }
+        let mut terrain = Terrain { width, height, elevation: heights };
+        terrain.normalize();
+        Ok(terrain)
+    }
+
+    /// Diamond-square fractal terrain. `roughness` in `0.0..=1.0` is how much of the
+    /// random displacement survives each halving of the step: low is smooth, high is rugged.
+    pub fn diamond_square<R: Rng + ?Sized>(width: usize, height: usize, roughness: f64, rng: &mut R) -> Self {
+        // square lattice of side 2^n + 1 covering the grid, cropped at the end
+        let side = width.max(height).max(2).next_power_of_two() + 1;
+        let mut lattice = vec![0.0; side * side];
+        let at = |x: usize, y: usize| y * side + x;
+
+        for (x, y) in [(0, 0), (side - 1, 0), (0, side - 1), (side - 1, side - 1)] {
+            lattice[at(x, y)] = rng.random_range(-1.0..1.0);
+        }
+
+        let mut step = side - 1;
+        let mut scale = 1.0;
+        while step > 1 {
+            let half = step / 2;
+
+            // diamond step: centre of every square
+            for y in (half..side).step_by(step) {
+                for x in (half..side).step_by(step) {
+                    let mean = (lattice[at(x - half, y - half)] + lattice[at(x + half, y - half)]
+                        + lattice[at(x - half, y + half)] + lattice[at(x + half, y + half)]) / 4.0;
+                    lattice[at(x, y)] = mean + rng.random_range(-scale..scale);
+                }
+            }
+
+            // square step: edge midpoints, averaging the corners that exist
+            for y in (0..side).step_by(half) {
+                let start = if (y / half).is_multiple_of(2) { half } else { 0 };
+                for x in (start..side).step_by(step) {
+                    let mut sum = 0.0;
+                    let mut count = 0.0;
+                    if x >= half { sum += lattice[at(x - half, y)]; count += 1.0; }
+                    if x + half < side { sum += lattice[at(x + half, y)]; count += 1.0; }
+                    if y >= half { sum += lattice[at(x, y - half)]; count += 1.0; }
+                    if y + half < side { sum += lattice[at(x, y + half)]; count += 1.0; }
+                    lattice[at(x, y)] = sum / count + rng.random_range(-scale..scale);
+                }
+            }
+
+            step = half;
+            scale *= roughness.clamp(0.0, 1.0).max(f64::MIN_POSITIVE);
+        }
+
+        let elevation = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| lattice[at(x, y)])
+            .collect();
+        let mut terrain = Terrain { width, height, elevation };
+        terrain.normalize();
+        terrain
+    }
+
+    /// Loads a heightmap from a PGM image (plain `P2` or binary `P5`)
+    /// or from a text file with one row of whitespace-separated numbers per line.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| format!("Cannot read heightmap {}: {}", path.display(), e))?;
+        match bytes.get(..2) {
+            Some(b"P2") | Some(b"P5") => Self::parse_pgm(&bytes),
+            _ => Self::parse_text(&String::from_utf8_lossy(&bytes)),
+        }
+    }
+
+    fn parse_text(text: &str) -> Result<Self, String> {
+        let rows: Vec<Vec<f64>> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|line| {
+                line.split([' ', '\t', ','])
+                    .filter(|v| !v.is_empty())
+                    .map(|v| v.parse::<f64>().map_err(|_| format!("Invalid height '{}'", v)))
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return Err("Heightmap rows must all have the same length".into());
+        }
+        Self::from_heights(width, rows.len(), rows.concat())
+    }
+
+    fn parse_pgm(bytes: &[u8]) -> Result<Self, String> {
+        let (width, height, _, heights) = read_pgm(bytes)?;
+        Self::from_heights(width, height, heights)
+    }
+
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> f64 {
+        self.elevation[y * self.width + x]
+    }
+
+    fn normalize(&mut self) {
+        let (min, max) = self.elevation.iter().fold((f64::MAX, f64::MIN), |(lo, hi), &h| (lo.min(h), hi.max(h)));
+        let range = max - min;
+        for h in &mut self.elevation {
+            *h = if range > 0.0 { (*h - min) / range } else { 0.0 };
+        }
+    }
+}
+
+/// Reads a PGM image (plain `P2` or binary `P5`): width, height, maximum value and
+/// the row-major pixel values. Fails if the image holds fewer pixels than announced.
+pub(crate) fn read_pgm(bytes: &[u8]) -> Result<(usize, usize, usize, Vec<f64>), String> {
+    // header: magic, width, height, maxval, separated by whitespace and comments
+    let mut fields = Vec::new();
+    let mut pos = 2;
+    while fields.len() < 3 {
+        match bytes.get(pos) {
+            None => return Err("Truncated PGM header".into()),
+            Some(b'#') => {
+                while bytes.get(pos).is_some_and(|&b| b != b'\n') { pos += 1; }
+            }
+            Some(b) if b.is_ascii_whitespace() => pos += 1,
+            Some(_) => {
+                let start = pos;
+                while bytes.get(pos).is_some_and(|b| !b.is_ascii_whitespace()) { pos += 1; }
+                let field = std::str::from_utf8(&bytes[start..pos]).ok().and_then(|f| f.parse::<usize>().ok());
+                fields.push(field.ok_or("Invalid PGM header")?);
+            }
+        }
+    }
+    let (width, height, maxval) = (fields[0], fields[1], fields[2]);
+    pos += 1; // the single whitespace after maxval
+
+    let mut values: Vec<f64> = if &bytes[..2] == b"P2" {
+        String::from_utf8_lossy(&bytes[pos.min(bytes.len())..])
+            .split_ascii_whitespace()
+            .map(|v| v.parse::<f64>().map_err(|_| format!("Invalid PGM value '{}'", v)))
+            .collect::<Result<_, _>>()?
+    } else if maxval < 256 {
+        bytes.get(pos..).unwrap_or_default().iter().map(|&b| b as f64).collect()
+    } else {
+        bytes.get(pos..).unwrap_or_default().chunks_exact(2).map(|p| u16::from_be_bytes([p[0], p[1]]) as f64).collect()
+    };
+    if values.len() < width * height {
+        return Err(format!("PGM image needs {}x{} values, got {}", width, height, values.len()));
+    }
+    values.truncate(width * height);
+    Ok((width, height, maxval, values))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::rng_from_seed;
+
+    #[test]
+    fn test_diamond_square_is_normalized_and_seeded() {
+        let a = Terrain::diamond_square(40, 25, 0.5, &mut rng_from_seed(3));
+        let b = Terrain::diamond_square(40, 25, 0.5, &mut rng_from_seed(3));
+        assert_eq!(a, b);
+        assert_eq!((a.width, a.height), (40, 25));
+
+        let min = a.elevation.iter().cloned().fold(f64::MAX, f64::min);
+        let max = a.elevation.iter().cloned().fold(f64::MIN, f64::max);
+        assert_eq!((min, max), (0.0, 1.0));
+    }
+
+    #[test]
+    fn test_parse_text_and_pgm() {
+        let text = Terrain::parse_text("# hill\n0 5 10\n5 10 5\n").unwrap();
+        assert_eq!((text.width, text.height), (3, 2));
+        assert_eq!(text.get(2, 0), 1.0);
+        assert_eq!(text.get(1, 0), 0.5);
+
+        let plain = Terrain::parse_pgm(b"P2\n# comment\n3 2\n10\n0 5 10\n5 10 5\n").unwrap();
+        assert_eq!(plain, text);
+
+        let binary = Terrain::parse_pgm(b"P5 3 2 255\n\x00\x05\x0a\x05\x0a\x05").unwrap();
+        assert_eq!(binary, text);
+
+        assert!(Terrain::parse_text("1 2\n3\n").is_err());
+        assert!(Terrain::parse_pgm(b"P2\n3 2\n10\n0 5\n").is_err());
+    }
+}
@@ -0,0 +1,210 @@
+use rand::Rng;
+use crate::config::Config;
+use crate::simulation::{run_simulations, SimulationResults};
+use crate::statistics::Z_95;
+
+/// The batch value that crosses the target at the critical density.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Criterion {
+    Percolation, // share of the forests with a cluster spanning the grid
+    Spanning,    // share of the runs whose fire crossed the grid
+    Burned,   // mean fraction of the trees burned per run
+}
+
+impl Criterion {
+    /// Name as accepted by `--criterion`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Criterion::Percolation => "percolation",
+            Criterion::Spanning => "spanning",
+            Criterion::Burned => "burned",
+        }
+    }
+}
+
+/// Search for the density at which the criterion crosses `target`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Threshold {
+    pub criterion: Criterion,
+    pub target: f64,    // value of the criterion at the critical density, 0.0 to 1.0
+    pub tolerance: f64, // the bisection stops once the density bracket is this narrow
+}
+
+impl Default for Threshold {
+    fn default() -> Self {
+        Threshold { criterion: Criterion::Percolation, target: 0.5, tolerance: 0.005 }
+    }
+}
+
+/// One density the search ran a batch at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThresholdPoint {
+    pub density: f64,
+    pub value: f64,     // the criterion, NaN when no run had trees
+    pub std_error: f64, // standard error of the value from the number of runs
+}
+
+#[derive(Debug)]
+pub struct ThresholdResults {
+    pub seed: u64,                   // master seed every batch was run with
+    pub points: Vec<ThresholdPoint>, // in the order they were evaluated
+    pub low: f64,                    // final bracket: below target at `low`, at or above at `high`
+    pub high: f64,
+    pub estimate: f64,  // critical density
+    pub std_error: f64, // of the estimate
+    pub slope: f64,     // change of the criterion per unit of density near the estimate, NaN if not measured
+}
+
+/// Points within this distance of the bracket feed the local fit of the criterion.
+const FIT_WINDOW: f64 = 0.05;
+
+/// Bisects the density between 0 and 1 until the bracket is narrower than the tolerance,
+/// one batch of `config.simulations` runs per midpoint. Every batch reuses the same
+/// master seed: with common random numbers the noisy criterion stays close to monotone.
+///
+/// The estimate is the root of a least-squares line through the points near the final
+/// bracket. Its standard error combines the noise of the criterion, carried through
+/// the slope of that line, with the width of the bracket.
+pub fn find_threshold(config: &Config, model: Threshold) -> ThresholdResults {
+    let seed = config.seed.unwrap_or_else(|| rand::rng().random());
+    let evaluate = |density: f64| {
+        let batch = Config {
+            density,
+            seed: Some(seed),
+            graphics: false,
+            record_runs: false,
+            // percolation is a property of the forest, read off its clusters
+            clusters: config.clusters || model.criterion == Criterion::Percolation,
+            ..config.clone()
+        };
+        measure(model.criterion, density, &run_simulations(&batch))
+    };
+
+    let (mut low, mut high) = (0.0, 1.0);
+    let mut points = Vec::new();
+    while high - low > model.tolerance {
+        let point = evaluate((low + high) / 2.0);
+        // a batch without trees never spans nor burns
+        if point.value >= model.target {
+            high = point.density;
+        } else {
+            low = point.density;
+        }
+        points.push(point);
+    }
+
+    let middle = (low + high) / 2.0;
+    let window = FIT_WINDOW.max(high - low);
+    let near: Vec<ThresholdPoint> = points
+        .iter()
+        .copied()
+        .filter(|p| (p.density - middle).abs() <= window && p.value.is_finite())
+        .collect();
+    let (estimate, slope) = match fit_line(&near) {
+        Some((intercept, slope)) if slope > 0.0 => {
+            let root = (model.target - intercept) / slope;
+            (root.clamp(middle - window, middle + window), slope)
+        }
+        _ => (middle, f64::NAN),
+    };
+
+    // a uniform position within the bracket has a variance of width²/12
+    let bracket_variance = (high - low).powi(2) / 12.0;
+    let noise = near
+        .iter()
+        .min_by(|a, b| (a.density - estimate).abs().total_cmp(&(b.density - estimate).abs()))
+        .map_or(f64::NAN, |p| p.std_error / slope);
+    let std_error = if noise.is_finite() { (noise.powi(2) + bracket_variance).sqrt() } else { bracket_variance.sqrt() };
+
+    ThresholdResults { seed, points, low, high, estimate, std_error, slope }
+}
+
+/// The criterion of a batch and its standard error.
+fn measure(criterion: Criterion, density: f64, results: &SimulationResults) -> ThresholdPoint {
+    let stats = &results.stats;
+    let (value, std_error) = match criterion {
+        // half the Wilson interval, which stays open when no run or every run spans
+        Criterion::Percolation => {
+            let (low, high) = stats.percolation.interval();
+            (stats.percolation.estimate(), (high - low) / (2.0 * Z_95))
+        }
+        Criterion::Spanning => {
+            let (low, high) = stats.spanning.interval();
+            (stats.spanning.estimate(), (high - low) / (2.0 * Z_95))
+        }
+        Criterion::Burned => (stats.mean / 100.0, stats.ci95 / Z_95 / 100.0),
+    };
+    ThresholdPoint { density, value, std_error }
+}
+
+/// Least-squares intercept and slope of the value against the density;
+/// None without two distinct densities.
+fn fit_line(points: &[ThresholdPoint]) -> Option<(f64, f64)> {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.density).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.value).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|p| (p.density - mean_x) * (p.value - mean_y)).sum();
+    let variance: f64 = points.iter().map(|p| (p.density - mean_x).powi(2)).sum();
+    (variance > 0.0).then(|| {
+        let slope = covariance / variance;
+        (mean_y - slope * mean_x, slope)
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BurnPattern;
+    use crate::fire_spread::{MooreNeighborhood, VonNeumannNeighborhood};
+
+    fn search_config(burn_pattern: BurnPattern) -> Config {
+        Config {
+            width: 40,
+            height: 40,
+            simulations: 40,
+            burn_pattern,
+            graphics: false,
+            seed: Some(11),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_line_fit() {
+        let points: Vec<ThresholdPoint> = [0.4, 0.5, 0.6]
+            .iter()
+            .map(|&density| ThresholdPoint { density, value: 2.0 * density - 0.5, std_error: 0.0 })
+            .collect();
+        let (intercept, slope) = fit_line(&points).unwrap();
+        assert!((intercept + 0.5).abs() < 1e-9 && (slope - 2.0).abs() < 1e-9);
+        assert_eq!(fit_line(&points[..1]), None);
+    }
+
+    #[test]
+    fn test_neighborhood_thresholds() {
+        // site percolation: p_c ≈ 0.407 on the Moore lattice, ≈ 0.593 on the square one
+        let model = Threshold { tolerance: 0.01, ..Threshold::default() };
+        let grid = |burn_pattern| Config { width: 64, height: 64, ..search_config(burn_pattern) };
+        let moore = find_threshold(&grid(BurnPattern::Moore(MooreNeighborhood)), model);
+        let von_neumann = find_threshold(&grid(BurnPattern::VonNeumann(VonNeumannNeighborhood)), model);
+
+        assert!(moore.high - moore.low <= 0.01);
+        // one batch per halving of the bracket
+        let halvings = (1.0 / model.tolerance).log2().ceil() as usize;
+        assert_eq!(moore.points.len(), halvings);
+        assert!((moore.estimate - 0.407).abs() < 0.02, "{}", moore.estimate);
+        assert!((von_neumann.estimate - 0.593).abs() < 0.02, "{}", von_neumann.estimate);
+        assert!(moore.std_error > 0.0 && moore.std_error < 0.05);
+        assert!(von_neumann.slope > 0.0);
+    }
+
+    #[test]
+    fn test_burned_criterion() {
+        let model = Threshold { criterion: Criterion::Burned, target: 0.5, tolerance: 0.02 };
+        let results = find_threshold(&search_config(BurnPattern::VonNeumann(VonNeumannNeighborhood)), model);
+        let below = results.points.iter().filter(|p| p.density <= results.low);
+        assert!(below.clone().count() > 0 && below.clone().all(|p| p.value < 0.5));
+        assert!(results.estimate > 0.5 && results.estimate < 0.7);
+    }
+}
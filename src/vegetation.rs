@@ -0,0 +1,213 @@
+use std::ops::{Index, IndexMut};
+use crate::forest::Fuel;
+
+/// What covers a cell. The fuel classes burn, water and rock never do;
+/// an empty cell keeps the class it was cleared of (conifer unless placed otherwise).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Vegetation {
+    Grass,
+    Shrub,
+    #[default]
+    Conifer,
+    Deciduous,
+    Water,
+    Rock,
+}
+
+impl Vegetation {
+    pub const COUNT: usize = 6;
+    pub const ALL: [Vegetation; Self::COUNT] = [
+        Vegetation::Grass,
+        Vegetation::Shrub,
+        Vegetation::Conifer,
+        Vegetation::Deciduous,
+        Vegetation::Water,
+        Vegetation::Rock,
+    ];
+
+    /// Name as accepted on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Vegetation::Grass => "grass",
+            Vegetation::Shrub => "shrub",
+            Vegetation::Conifer => "conifer",
+            Vegetation::Deciduous => "deciduous",
+            Vegetation::Water => "water",
+            Vegetation::Rock => "rock",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.name() == name.trim().to_lowercase())
+    }
+
+    /// Whether cells of this class hold trees that can burn.
+    pub fn is_fuel(self) -> bool {
+        !matches!(self, Vegetation::Water | Vegetation::Rock)
+    }
+
+    /// Glyph of an unburnt cell of this class; two columns wide like the other cells.
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Vegetation::Grass => "🌾",
+            Vegetation::Shrub => "🌿",
+            Vegetation::Conifer => "🌲",
+            Vegetation::Deciduous => "🌳",
+            Vegetation::Water => "🌊",
+            Vegetation::Rock => "🪨",
+        }
+    }
+}
+
+/// Flammability of one vegetation class.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VegetationClass {
+    pub ignition: f64, // scales the spread probability into cells of this class
+    pub fuel: Fuel,
+}
+
+/// Flammability of every class, indexed by [`Vegetation`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VegetationTable([VegetationClass; Vegetation::COUNT]);
+
+impl Default for VegetationTable {
+    /// Conifer is the classic tree: certain ignition, burnt out in one step.
+    fn default() -> Self {
+        let class = |ignition, burn_steps| VegetationClass {
+            ignition,
+            fuel: Fuel { burn_steps, ..Fuel::default() },
+        };
+        VegetationTable([
+            class(1.0, 1), // grass catches easily and burns fast
+            class(0.8, 2),
+            class(1.0, 1),
+            class(0.5, 3), // broadleaves are slow to catch but burn long
+            class(0.0, 1),
+            class(0.0, 1),
+        ])
+    }
+}
+
+impl Index<Vegetation> for VegetationTable {
+    type Output = VegetationClass;
+
+    fn index(&self, vegetation: Vegetation) -> &VegetationClass {
+        &self.0[vegetation as usize]
+    }
+}
+
+impl IndexMut<Vegetation> for VegetationTable {
+    fn index_mut(&mut self, vegetation: Vegetation) -> &mut VegetationClass {
+        &mut self.0[vegetation as usize]
+    }
+}
+
+/// How the grid is covered: fuel classes as shares of the tree density,
+/// water and rock as absolute fractions of the grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VegetationMix([f64; Vegetation::COUNT]);
+
+impl Default for VegetationMix {
+    /// Conifers only.
+    fn default() -> Self {
+        let mut mix = [0.0; Vegetation::COUNT];
+        mix[Vegetation::Conifer as usize] = 1.0;
+        VegetationMix(mix)
+    }
+}
+
+impl VegetationMix {
+    /// Parses "<class>=<density>,..." with absolute densities, e.g. "grass=0.2,conifer=0.3,water=0.05".
+    /// Returns the mix and the total density of the fuel classes.
+    pub fn parse(spec: &str) -> Result<(Self, f64), String> {
+        let mut densities = [0.0; Vegetation::COUNT];
+        for part in spec.split(',') {
+            let (name, value) = part.split_once('=').ok_or("Vegetation must be '<class>=<density>,...'")?;
+            let vegetation = Vegetation::parse(name).ok_or_else(|| format!("Unknown vegetation class '{}'", name))?;
+            let density: f64 = value.trim().parse().map_err(|_| format!("Invalid density for {}", name))?;
+            if !(0.0..=1.0).contains(&density) {
+                return Err(format!("Density of {} must be between 0.0 and 1.0", name));
+            }
+            densities[vegetation as usize] = density;
+        }
+
+        let (mix, fuel) = Self::from_densities(densities);
+        if fuel + mix.barren() > 1.0 + 1e-9 {
+            return Err("Vegetation densities add up to more than 1.0".into());
+        }
+        Ok((mix, fuel))
+    }
+
+    /// The mix of a grid with `counts` cells of every class (trees for the fuel classes)
+    /// out of `cells`, and its tree density.
+    pub fn from_counts(counts: &[usize; Vegetation::COUNT], cells: usize) -> (Self, f64) {
+        Self::from_densities(counts.map(|count| count as f64 / cells as f64))
+    }
+
+    fn from_densities(mut densities: [f64; Vegetation::COUNT]) -> (Self, f64) {
+        let fuel: f64 = Vegetation::ALL.iter().filter(|v| v.is_fuel()).map(|&v| densities[v as usize]).sum();
+        if fuel > 0.0 {
+            for vegetation in Vegetation::ALL.into_iter().filter(|v| v.is_fuel()) {
+                densities[vegetation as usize] /= fuel;
+            }
+        } else {
+            densities[Vegetation::Conifer as usize] = 1.0;
+        }
+        (VegetationMix(densities), fuel)
+    }
+
+    /// Fraction of the grid covered by water and rock.
+    pub fn barren(&self) -> f64 {
+        self.0[Vegetation::Water as usize] + self.0[Vegetation::Rock as usize]
+    }
+
+    /// Absolute density of every present class for a total tree density of `density`.
+    pub fn densities(&self, density: f64) -> Vec<(Vegetation, f64)> {
+        Vegetation::ALL
+            .into_iter()
+            .map(|v| (v, if v.is_fuel() { self.0[v as usize] * density } else { self.0[v as usize] }))
+            .filter(|&(_, d)| d > 0.0)
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for vegetation in Vegetation::ALL {
+            assert_eq!(Vegetation::parse(vegetation.name()), Some(vegetation));
+        }
+        assert_eq!(Vegetation::parse(" Grass"), Some(Vegetation::Grass));
+        assert_eq!(Vegetation::parse("cactus"), None);
+        assert!(!Vegetation::Water.is_fuel() && Vegetation::Shrub.is_fuel());
+    }
+
+    #[test]
+    fn test_mix_keeps_shares_and_barren_ground() {
+        let (mix, density) = VegetationMix::parse("grass=0.2,conifer=0.3,water=0.05").unwrap();
+        assert!((density - 0.5).abs() < 1e-12);
+        assert!((mix.barren() - 0.05).abs() < 1e-12);
+
+        // a lower tree density keeps the proportions between the fuel classes
+        let densities = mix.densities(0.25);
+        assert_eq!(densities.len(), 3);
+        assert_eq!(densities[0].0, Vegetation::Grass);
+        assert!((densities[0].1 - 0.1).abs() < 1e-12);
+        assert!((densities[1].1 - 0.15).abs() < 1e-12);
+        assert_eq!(densities[2], (Vegetation::Water, 0.05));
+
+        assert_eq!(VegetationMix::default().densities(0.6), vec![(Vegetation::Conifer, 0.6)]);
+        assert!(VegetationMix::parse("grass=0.7,rock=0.4").is_err());
+        assert!(VegetationMix::parse("grass").is_err());
+        assert!(VegetationMix::parse("moss=0.1").is_err());
+
+        let mut counts = [0; Vegetation::COUNT];
+        (counts[Vegetation::Shrub as usize], counts[Vegetation::Rock as usize]) = (30, 10);
+        let (mix, density) = VegetationMix::from_counts(&counts, 100);
+        assert_eq!(mix.densities(density), vec![(Vegetation::Shrub, 0.3), (Vegetation::Rock, 0.1)]);
+    }
+}
@@ -7,12 +7,44 @@ fn test_full_workflow() {
         "-s".to_string(), "50".to_string(),
         "-d".to_string(), "0.6".to_string(),
         "-c".to_string(), "10".to_string(),
+        "-g-off".to_string(),
     ];
 
     let config = Config::new(&args).unwrap();
     let results = run_simulations(&config);
 
     assert!(results.average_burned > 0.0);
-    assert!(results.min_burned >= 0.0 && results.min_burned <= 1.0);
-    assert!(results.max_burned >= 0.0 && results.max_burned <= 1.0);
-}
\ No newline at end of file
+    assert!(results.min_burned >= 0.0 && results.min_burned <= 100.0);
+    assert!(results.max_burned >= 0.0 && results.max_burned <= 100.0);
+}
+
+#[test]
+fn test_seeded_workflow_repeats() {
+    let args: Vec<String> = ["program_name", "-s", "30", "-d", "0.5", "-c", "5", "-g-off", "--seed", "2025"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+    let config = Config::new(&args).unwrap();
+    let first = run_simulations(&config);
+    let second = run_simulations(&config);
+
+    assert_eq!(first.seed, 2025);
+    assert_eq!(first.average_burned, second.average_burned);
+}
+
+#[test]
+fn test_river_map_stops_the_fire() {
+    let args: Vec<String> = ["program_name", "--input-map", "maps/river.txt", "-c", "3", "-g-off", "--seed", "1"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+    let config = Config::new(&args).unwrap();
+    assert_eq!((config.width, config.height), (12, 6));
+    let results = run_simulations(&config);
+
+    // the west stand burns out, the river shields the east one
+    assert_eq!(results.min_burned, 50.0);
+    assert_eq!(results.max_burned, 50.0);
+}